@@ -43,4 +43,155 @@ pub enum ErrorCode {
     // 12
     #[msg("RnsIs doesn't matched.")]
     RnsIsNotMatch,
+
+    // 13
+    #[msg("The discount campaign is not active.")]
+    CampaignInactive,
+
+    #[msg("The discount campaign has no vouchers left.")]
+    VoucherExhausted,
+
+    #[msg("The voucher proof is invalid.")]
+    InvalidVoucherProof,
+
+    #[msg("The voucher accounts are missing or don't match the voucher.")]
+    InvalidVoucherAccounts,
+
+    #[msg("The discount value is out of range.")]
+    InvalidDiscount,
+
+    #[msg("The referrer doesn't hold a valid Legal DID.")]
+    InvalidReferrer,
+
+    #[msg("There are no referral rewards to claim.")]
+    NoReferralRewards,
+
+    #[msg("A sponsored authorization requires a sponsor receipt account.")]
+    MissingSponsorReceipt,
+
+    #[msg("There is no pending authorization to cancel.")]
+    NotAuthorized,
+
+    #[msg("The mint price exceeds the maximum price accepted.")]
+    PriceExceedsMax,
+
+    #[msg("The price change must take effect in the future.")]
+    InvalidEffectiveTime,
+
+    #[msg("The token index must be the next index allocated by the project.")]
+    InvalidTokenIndex,
+
+    #[msg("Only a burned DID of the same rns_id and wallet can be reissued once.")]
+    InvalidReissue,
+
+    #[msg("This rns_id has been issued to the maximum number of wallets.")]
+    RnsIdWalletLimitReached,
+
+    #[msg("The DID has already been burned or revoked.")]
+    DidNotActive,
+
+    #[msg("The wallet index has no room for another DID.")]
+    WalletIndexFull,
+
+    #[msg("The rns_id is longer than the maximum length.")]
+    RnsIdTooLong,
+
+    #[msg("The merkle root must be 32 hex-encoded bytes.")]
+    InvalidMerkleRoot,
+
+    #[msg("The account is already on the latest layout.")]
+    AlreadyMigrated,
+
+    #[msg("The account does not hold the expected data.")]
+    InvalidLegacyAccount,
+
+    #[msg("The freed rent must go back to whoever paid it.")]
+    InvalidRentReceiver,
+
+    #[msg("Migrating a DID status needs the wallet index account.")]
+    MissingWalletIndex,
+
+    #[msg("Privacy mode only accepts salted rns_id commitments.")]
+    RnsIdNotCommitted,
+
+    #[msg("The rns_id and salt don't match the DID commitment.")]
+    InvalidRnsIdProof,
+
+    #[msg("The token was not issued by the v1 project.")]
+    NotV1Did,
+
+    #[msg("The URI template is longer than the maximum length.")]
+    UriTemplateTooLong,
+
+    #[msg("The metadata URI is longer than Metaplex allows.")]
+    UriTooLong,
+
+    #[msg("The collection is already sized.")]
+    CollectionAlreadySized,
+
+    #[msg("The rule set is already created.")]
+    RuleSetAlreadyCreated,
+
+    #[msg("The rule set has not been created.")]
+    RuleSetMissing,
+
+    #[msg("Programmable DIDs need the token record and instructions sysvar accounts.")]
+    MissingProgrammableAccounts,
+
+    #[msg("Creators must be unique, at most 4 and share 100% between them.")]
+    InvalidCreators,
+
+    #[msg("The name template is longer than the maximum length.")]
+    NameTemplateTooLong,
+
+    #[msg("The rendered name is longer than the 32 characters Metaplex allows.")]
+    NameTooLong,
+
+    #[msg("Tiers are numbered from 1.")]
+    InvalidTierNumber,
+
+    #[msg("The tier is not active.")]
+    TierInactive,
+
+    #[msg("The tier has reached its max supply.")]
+    TierSoldOut,
+
+    #[msg("The tier or collection accounts don't match the DID.")]
+    TierMismatch,
+
+    #[msg("Jurisdictions are ISO 3166-1 alpha-2 codes.")]
+    InvalidJurisdiction,
+
+    #[msg("DIDs can't be issued in this jurisdiction.")]
+    JurisdictionDenied,
+
+    #[msg("The jurisdiction denylist is full.")]
+    JurisdictionDenylistFull,
+
+    #[msg("The jurisdiction attestation needs an attestor and the instructions sysvar.")]
+    MissingJurisdictionAttestation,
+
+    #[msg("Wallets are screened against a sanctions list, a non-membership proof is required.")]
+    SanctionsProofRequired,
+
+    #[msg("The sanctions non-membership proof is invalid.")]
+    InvalidSanctionsProof,
+
+    #[msg("The project is in allowlist mode and neither the wallet nor the rns_id is allowlisted.")]
+    NotAllowlisted,
+
+    #[msg("Only the payer of an expired authorization can renew it.")]
+    RenewalPayerMismatch,
+
+    #[msg("The rns_id of this DID was too long to keep, its metadata can't be rebuilt.")]
+    RnsIdUnavailable,
+
+    #[msg("A v1 DID can only be exchanged together with the reissue of its replacement.")]
+    MissingReissue,
+
+    #[msg("Jurisdictions are denied, an attested jurisdiction is required.")]
+    JurisdictionRequired,
+
+    #[msg("Privacy mode requires the commitment to be attested by the commitment attestor.")]
+    MissingCommitmentAttestation,
 }
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+#[event]
+pub struct AllowlistEntryEvent {
+  pub subject: [u8; 32],
+  pub is_allowed: bool,
+}
+
+#[derive(Accounts)]
+#[instruction(subject: [u8; 32])]
+pub struct AddAllowlistEntryContext<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+
+  #[account(
+    has_one = authority,
+    seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_ref()],
+    bump = non_transferable_project.bump
+  )]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+  #[account(
+    init,
+    payer = authority,
+    space = NON_TRANSFERABLE_ALLOWLIST_ENTRY_SIZE,
+    seeds = [NON_TRANSFERABLE_ALLOWLIST_PREFIX.as_ref(), subject.as_ref()],
+    bump
+  )]
+  pub allowlist_entry: Box<Account<'info, AllowlistEntryAccount>>,
+
+  pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<AddAllowlistEntryContext>, subject: [u8; 32]) -> Result<()> {
+  let entry = &mut ctx.accounts.allowlist_entry;
+  entry.version = ACCOUNT_VERSION;
+  entry.subject = subject;
+  entry.bump = *ctx.bumps.get("allowlist_entry").unwrap();
+
+  emit!(AllowlistEntryEvent {
+    subject,
+    is_allowed: true,
+  });
+
+  Ok(())
+}
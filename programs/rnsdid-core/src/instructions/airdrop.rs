@@ -16,7 +16,14 @@ pub struct AirdropEvent {
 }
 
 #[derive(Accounts)]
-#[instruction(rns_id: String, wallet:Pubkey, merkle_root: String, index: String)]
+#[instruction(
+  rns_id: String,
+  wallet:Pubkey,
+  merkle_root: String,
+  index: String,
+  jurisdiction: Option<[u8; 2]>,
+  sanctions_proof: Option<SanctionsProof>
+)]
 pub struct MintNonTransferableNft<'info> {
   #[account(mut)]
   pub authority: Signer<'info>,
@@ -45,7 +52,7 @@ pub struct MintNonTransferableNft<'info> {
   pub non_transferable_project_master_edition: AccountInfo<'info>,
 
   #[account(
-    init_if_needed,
+    init,
     payer = authority,
     seeds = [
       NON_TRANSFERABLE_NFT_MINT_PREFIX.as_ref(),
@@ -73,12 +80,7 @@ pub struct MintNonTransferableNft<'info> {
   #[account(
       init_if_needed,
       payer = authority,
-      space = 8 +
-      32 +        // authority
-      50 +        // rns_id
-      1 +         // is_minted
-      1 +         // is_authorized
-      1,          // bump
+      space = NON_TRANSFERABLE_USER_STATUS_SIZE,
       seeds = [
           NON_TRANSFERABLE_NFT_USERSTATUS_PREFIX.as_ref(),
           &hash_seed(&rns_id)[..32],
@@ -92,9 +94,7 @@ pub struct MintNonTransferableNft<'info> {
   #[account(
     init_if_needed,
     payer = authority,
-    space = 8 +
-    8 +
-    32,
+    space = NON_TRANSFERABLE_RNS_ID_STATUS_SIZE,
     seeds = [
         NON_TRANSFERABLE_NFT_RNSID_PREFIX.as_ref(),
         &hash_seed(&rns_id)[..32],
@@ -105,11 +105,9 @@ pub struct MintNonTransferableNft<'info> {
 
 
   #[account(
-    init_if_needed,
+    init,
     payer = authority,
-    space = 8 +
-    400 +
-    32,
+    space = NON_TRANSFERABLE_NFT_STATUS_SIZE,
     seeds = [
         NON_TRANSFERABLE_NFT_STATUS_PREFIX.as_ref(),
         non_transferable_nft_mint.key().as_ref()
@@ -143,36 +141,15 @@ impl<'info> MintNonTransferableNft<'info> {
   }
 }
 
-pub fn handler(ctx: Context<MintNonTransferableNft>, rns_id: String, wallet:Pubkey, _merkle_root: String, _index: String) -> Result<()> {
-
-  let project_signer_seeds = [
-    NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(),
-    &[ctx.accounts.non_transferable_project.bump],
-  ];
-
-  msg!("mint_to start!");
+/// Mints the DID to the holder and freezes it, so it cannot be transferred.
+fn mint_and_freeze(ctx: &Context<MintNonTransferableNft>, project_signer_seeds: &[&[u8]]) -> Result<()> {
   token::mint_to(
     ctx
       .accounts
       .airdrop_ctx()
-      .with_signer(&[&project_signer_seeds[..]]),
+      .with_signer(&[project_signer_seeds]),
     1,
   )?;
-  msg!("mint_to done!");
-
-  // Check if the wallet is blacklisted
-  let state = &ctx.accounts.non_transferable_project;
-
-  // Check if the wallet is blacklisted
-  require!(
-    !state.is_blocked_address(wallet),
-    ErrorCode::WalletBlacklisted
-  );
-  // Check if the LDID is blacklisted
-  require!(!state.is_blocked_rns_id(rns_id.clone()), ErrorCode::LdidBlacklisted);
-
-
-  msg!("freeze_account start");
 
   let ix = freeze_account(
     &spl_token::ID,
@@ -197,13 +174,68 @@ pub fn handler(ctx: Context<MintNonTransferableNft>, rns_id: String, wallet:Pubk
     ctx.accounts.token_program.to_account_info().clone(),
   ];
 
-  solana_program::program::invoke_signed(&ix, &accounts, &[&project_signer_seeds[..]])?;
-  msg!("freeze_account done");
+  solana_program::program::invoke_signed(&ix, &accounts, &[project_signer_seeds])?;
+
+  Ok(())
+}
+
+pub fn handler(
+  ctx: Context<MintNonTransferableNft>,
+  rns_id: String,
+  wallet: Pubkey,
+  _merkle_root: String,
+  index: String,
+  jurisdiction: Option<[u8; 2]>,
+  sanctions_proof: Option<SanctionsProof>,
+) -> Result<()> {
+  ctx.accounts.non_transferable_project.check_rns_id(&rns_id)?;
+
+  // Indexes are allocated by the project counter, never chosen freely by the caller.
+  let token_index = ctx.accounts.non_transferable_project.next_token_index;
+  require!(index == token_index.to_string(), ErrorCode::InvalidTokenIndex);
+  ctx.accounts.non_transferable_project.next_token_index = token_index + 1;
+
+  let nft_status = &mut ctx.accounts.non_transferable_nft_status;
+  nft_status.version = ACCOUNT_VERSION;
+  nft_status.bump = *ctx.bumps.get("non_transferable_nft_status").unwrap();
+  nft_status.mint = ctx.accounts.non_transferable_nft_mint.key();
+  nft_status.token_index = token_index;
+  nft_status.payer = ctx.accounts.authority.key();
+
+  let project_signer_seeds = [
+    NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(),
+    &[ctx.accounts.non_transferable_project.bump],
+  ];
+
+  // Check if the wallet is blacklisted
+  let state = &ctx.accounts.non_transferable_project;
+
+  // Check if the wallet is blacklisted
+  require!(
+    !state.is_blocked_address(wallet),
+    ErrorCode::WalletBlacklisted
+  );
+  // Check if the LDID is blacklisted
+  require!(!state.is_blocked_rns_id(rns_id.clone()), ErrorCode::LdidBlacklisted);
+  state.check_sanctions(ctx.accounts.user_account.key(), &sanctions_proof)?;
+  // attested in `authorize_mint` or supplied by the operator, the denylist
+  // may have changed since the attestation
+  let jurisdiction =
+    state.issuance_jurisdiction(ctx.accounts.non_transferable_user_status.jurisdiction, jurisdiction)?;
+
+
+  // Programmable DIDs are minted in `verify`, once their metadata exists, and
+  // kept frozen by token metadata itself.
+  if !state.programmable {
+    mint_and_freeze(&ctx, &project_signer_seeds)?;
+  }
+  // kept for `verify`, which records it on the DID
+  ctx.accounts.non_transferable_user_status.jurisdiction = jurisdiction;
 
   emit!(AirdropEvent {
     rns_id: rns_id.clone(),
-    wallet: ctx.accounts.authority.key(),
-    token_id: rns_id.clone()
+    wallet,
+    token_id: index
   });
 
   msg!(
@@ -3,22 +3,53 @@ use anchor_lang::{
     solana_program::{
         program::invoke,
         system_instruction,
+        sysvar,
     }
 };
 
 use crate::error::ErrorCode;
 use crate::state::*;
+use crate::utils::{merkle_leaf, verify_ed25519_instruction, verify_merkle_proof};
 
 
 #[event]
 pub struct AuthorizeMintEvent {
     pub rns_id: String,
     pub wallet: Pubkey,
+    pub payer: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct VoucherRedeemedEvent {
+    pub campaign_id: u64,
+    pub wallet: Pubkey,
+    pub discount: u64,
+}
+
+#[event]
+pub struct ReferralEvent {
+    pub referrer: Pubkey,
+    pub wallet: Pubkey,
+    pub reward: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct AuthorizeMintArgs {
+    pub voucher: Option<DiscountVoucher>,
+    pub referrer: Option<Pubkey>,
+    /// Highest price the payer accepts, none to accept any.
+    pub max_price: Option<u64>,
+    /// Attested by the project jurisdiction attestor, required while codes are denied.
+    pub jurisdiction: Option<[u8; 2]>,
+    pub sanctions_proof: Option<SanctionsProof>,
+    pub allowlist_proof: Option<Vec<[u8; 32]>>,
 }
 
 #[derive(Accounts)]
-#[instruction(rns_id: String, wallet: Pubkey)]
+#[instruction(rns_id: String, wallet: Pubkey, args: AuthorizeMintArgs)]
 pub struct AuthorizeMintContext<'info> {
+    /// Pays the mint price. May differ from `wallet`, the beneficiary.
     #[account(mut)]
     pub authority: Signer<'info>,
 
@@ -29,16 +60,13 @@ pub struct AuthorizeMintContext<'info> {
     )]
     pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
 
+    /// Tier to authorize, charged at its price. None for the project collection.
+    pub tier: Option<Box<Account<'info, TierAccount>>>,
 
     #[account(
         init_if_needed,
         payer = authority,
-        space = 8 +
-        32 +        // authority
-        50 +        // rns_id
-        1 +         // is_minted
-        1 +         // is_authorized
-        1,          // bump
+        space = NON_TRANSFERABLE_USER_STATUS_SIZE,
         seeds = [
             NON_TRANSFERABLE_NFT_USERSTATUS_PREFIX.as_ref(),
             &hash_seed(&rns_id)[..32],
@@ -48,26 +76,315 @@ pub struct AuthorizeMintContext<'info> {
     )]
     pub non_transferable_user_status: Box<Account<'info, UserStatusAccount>>,
 
-    /// CHECK: This is not dangerous because we don't read or write from this account
-    #[account(mut)]
-    pub fee_recipient: AccountInfo<'info>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = NON_TRANSFERABLE_SPONSOR_RECEIPT_SIZE,
+        seeds = [
+            NON_TRANSFERABLE_SPONSOR_RECEIPT_PREFIX.as_ref(),
+            non_transferable_user_status.key().as_ref()
+        ],
+        bump
+    )]
+    pub sponsor_receipt: Option<Box<Account<'info, SponsorReceiptAccount>>>,
+
+    #[account(
+        mut,
+        seeds = [
+            NON_TRANSFERABLE_CAMPAIGN_PREFIX.as_ref(),
+            DiscountVoucher::campaign_seed(&args.voucher).as_ref()
+        ],
+        bump = campaign.bump
+    )]
+    pub campaign: Option<Box<Account<'info, CampaignAccount>>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = NON_TRANSFERABLE_VOUCHER_REDEMPTION_SIZE,
+        seeds = [
+            NON_TRANSFERABLE_VOUCHER_PREFIX.as_ref(),
+            DiscountVoucher::campaign_seed(&args.voucher).as_ref(),
+            wallet.key().as_ref()
+        ],
+        bump
+    )]
+    pub voucher_redemption: Option<Box<Account<'info, VoucherRedemptionAccount>>>,
+
+    /// Entry of the wallet or the rns_id, checked in allowlist mode.
+    pub allowlist_entry: Option<Box<Account<'info, AllowlistEntryAccount>>>,
+
+    /// The referrer's own Legal DID status.
+    pub referrer_nft_status: Option<Box<Account<'info, NftStatusAccount>>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = NON_TRANSFERABLE_REFERRAL_STATS_SIZE,
+        seeds = [
+            NON_TRANSFERABLE_REFERRAL_PREFIX.as_ref(),
+            args.referrer.unwrap_or_default().as_ref()
+        ],
+        bump
+    )]
+    pub referral_stats: Option<Box<Account<'info, ReferralStatsAccount>>>,
+
+    /// CHECK: Only read to find the ed25519 voucher, jurisdiction and commitment signatures
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: Option<UncheckedAccount<'info>>,
 
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
+/// Message an issuer signs to grant `wallet` a voucher of a signature campaign.
+pub fn voucher_message(campaign: &Pubkey, wallet: &Pubkey, rns_id: &str) -> Vec<u8> {
+    [campaign.as_ref(), wallet.as_ref(), &hash_seed(rns_id)[..32]].concat()
+}
+
+/// Message the project attestor signs to vouch for the jurisdiction of `wallet`.
+pub fn jurisdiction_message(wallet: &Pubkey, rns_id: &str, jurisdiction: [u8; 2]) -> Vec<u8> {
+    [wallet.as_ref(), &hash_seed(rns_id)[..32], &jurisdiction].concat()
+}
+
+/// Message the commitment attestor signs for a commitment it derived the salt of.
+pub fn commitment_message(wallet: &Pubkey, commitment: &str) -> Vec<u8> {
+    [wallet.as_ref(), &hash_seed(commitment)[..32]].concat()
+}
+
+/// In privacy mode the salt is the operator's, not the caller's: a salt of
+/// their own would let a wallet commit to the same rns_id many times.
+fn check_commitment(ctx: &Context<AuthorizeMintContext>, commitment: &str, wallet: Pubkey) -> Result<()> {
+    let project = &ctx.accounts.non_transferable_project;
+    if !project.privacy_mode {
+        return Ok(());
+    }
+
+    let Some(instructions) = ctx.accounts.instructions.as_ref() else {
+        return err!(ErrorCode::MissingCommitmentAttestation);
+    };
+    require!(
+        project.commitment_attestor != Pubkey::default(),
+        ErrorCode::MissingCommitmentAttestation
+    );
+    verify_ed25519_instruction(
+        instructions,
+        &project.commitment_attestor,
+        &commitment_message(&wallet, commitment),
+    )
+}
+
+fn check_jurisdiction(
+    ctx: &Context<AuthorizeMintContext>,
+    rns_id: &str,
+    wallet: Pubkey,
+    jurisdiction: [u8; 2],
+) -> Result<()> {
+    let project = &ctx.accounts.non_transferable_project;
+    project.check_jurisdiction(jurisdiction)?;
+
+    let Some(instructions) = ctx.accounts.instructions.as_ref() else {
+        return err!(ErrorCode::MissingJurisdictionAttestation);
+    };
+    require!(
+        project.jurisdiction_attestor != Pubkey::default(),
+        ErrorCode::MissingJurisdictionAttestation
+    );
+    verify_ed25519_instruction(
+        instructions,
+        &project.jurisdiction_attestor,
+        &jurisdiction_message(&wallet, rns_id, jurisdiction),
+    )
+}
+
+fn redeem_voucher(
+    ctx: &mut Context<AuthorizeMintContext>,
+    voucher: &DiscountVoucher,
+    rns_id: &str,
+    wallet: Pubkey,
+    price: u64,
+) -> Result<u64> {
+    let accounts = &mut ctx.accounts;
+    let (Some(campaign), Some(redemption)) = (accounts.campaign.as_mut(), accounts.voucher_redemption.as_mut()) else {
+        return err!(ErrorCode::InvalidVoucherAccounts);
+    };
+
+    require!(campaign.is_active, ErrorCode::CampaignInactive);
+    require!(!campaign.is_exhausted(), ErrorCode::VoucherExhausted);
+
+    match campaign.kind {
+        VoucherKind::Merkle => {
+            require!(
+                verify_merkle_proof(&voucher.proof, &campaign.merkle_root, merkle_leaf(wallet.as_ref())),
+                ErrorCode::InvalidVoucherProof
+            );
+        }
+        VoucherKind::Signature => {
+            let Some(instructions) = accounts.instructions.as_ref() else {
+                return err!(ErrorCode::InvalidVoucherAccounts);
+            };
+            verify_ed25519_instruction(
+                instructions,
+                &campaign.issuer,
+                &voucher_message(&campaign.key(), &wallet, rns_id),
+            )?;
+        }
+    }
+
+    let discount = campaign.discount_for(price);
+    campaign.used += 1;
+
+    redemption.campaign = campaign.key();
+    redemption.wallet = wallet;
+    redemption.discount = discount;
+    redemption.version = ACCOUNT_VERSION;
+    redemption.bump = *ctx.bumps.get("voucher_redemption").unwrap();
+
+    emit!(VoucherRedeemedEvent {
+        campaign_id: campaign.campaign_id,
+        wallet,
+        discount,
+    });
+
+    Ok(price - discount)
+}
+
+/// Checks the referrer and returns the part of `amount` set aside as its
+/// reward. The referral is counted once `verify` pays the reward out.
+fn record_referral(
+    ctx: &mut Context<AuthorizeMintContext>,
+    referrer: Pubkey,
+    wallet: Pubkey,
+    amount: u64,
+) -> Result<u64> {
+    let accounts = &mut ctx.accounts;
+    let (Some(referrer_status), Some(stats)) = (accounts.referrer_nft_status.as_ref(), accounts.referral_stats.as_mut()) else {
+        return err!(ErrorCode::InvalidReferrer);
+    };
+
+    // Only holders of a live, non-blocked Legal DID can refer, and never
+    // themselves: the reward would come back as a discount. The display rns_id
+    // is empty or a commitment for some DIDs, the hash always identifies it.
+    let project = &accounts.non_transferable_project;
+    require!(
+        referrer != wallet
+            && referrer != accounts.authority.key()
+            && referrer_status.authority == referrer
+            && referrer_status.is_active()
+            && !project.is_blocked_address(referrer)
+            && !project.is_blocked_rns_id_hash(&referrer_status.rns_id_hash),
+        ErrorCode::InvalidReferrer
+    );
+
+    let reward = project.referral_reward(amount);
+
+    stats.referrer = referrer;
+    stats.version = ACCOUNT_VERSION;
+    stats.bump = *ctx.bumps.get("referral_stats").unwrap();
+
+    emit!(ReferralEvent {
+        referrer,
+        wallet,
+        reward,
+    });
+
+    Ok(reward)
+}
+
+fn transfer_lamports<'info>(
+    from: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    invoke(
+        &system_instruction::transfer(from.key, to.key, amount),
+        &[from.clone(), to.clone(), system_program.clone()],
+    )?;
+    Ok(())
+}
+
 pub fn handler(
-    ctx: Context<AuthorizeMintContext>,
+    mut ctx: Context<AuthorizeMintContext>,
     rns_id: String,
-    _wallet: Pubkey
+    wallet: Pubkey,
+    args: AuthorizeMintArgs,
 ) -> Result<()> {
+    let AuthorizeMintArgs {
+        voucher,
+        referrer,
+        max_price,
+        jurisdiction,
+        sanctions_proof,
+        allowlist_proof,
+    } = args;
+    ctx.accounts.non_transferable_project.check_rns_id(&rns_id)?;
+    check_commitment(&ctx, &rns_id, wallet)?;
+    require!(
+        !ctx.accounts.non_transferable_project.is_blocked_address(wallet),
+        ErrorCode::WalletBlacklisted
+    );
+    require!(
+        !ctx.accounts.non_transferable_project.is_blocked_rns_id(rns_id.clone()),
+        ErrorCode::LdidBlacklisted
+    );
+    ctx.accounts.non_transferable_project.check_sanctions(wallet, &sanctions_proof)?;
+    ctx.accounts.non_transferable_project.check_allowlist(
+        wallet,
+        &rns_id,
+        ctx.accounts.allowlist_entry.as_deref().map(|entry| &**entry),
+        &allowlist_proof,
+    )?;
+    match jurisdiction {
+        Some(jurisdiction) => check_jurisdiction(&ctx, &rns_id, wallet, jurisdiction)?,
+        None => require!(
+            ctx.accounts.non_transferable_project.denied_jurisdictions.is_empty(),
+            ErrorCode::JurisdictionRequired
+        ),
+    }
+
+    let payer = ctx.accounts.authority.key();
+    let slot = Clock::get()?.slot;
+    let status = &ctx.accounts.non_transferable_user_status;
+    // An expired authorization can be renewed without any manual cleanup.
+    require!(
+        !status.is_authorized || status.is_authorization_expired(slot),
+        ErrorCode::LDIDHasAuthorized
+    );
+    // The payment escrowed by an authorization that expired unused counts
+    // towards its renewal, so it can still be refunded by `cancel_authorization`.
+    let (kept_payment, kept_reward) = if status.is_authorization_expired(slot) {
+        (status.paid_amount, status.referral_reward)
+    } else {
+        (0, 0)
+    };
+    let credit = kept_payment + kept_reward;
+    if credit > 0 {
+        require_keys_eq!(payer, status.payer, ErrorCode::RenewalPayerMismatch);
+        require!(referrer.is_none() || status.referral_reward == 0, ErrorCode::InvalidReferrer);
+    }
 
-    let amount = ctx.accounts.non_transferable_project.mint_price;
-    let fee_recipient = ctx.accounts.non_transferable_project.fee_recipient;
+    let now = Clock::get()?.unix_timestamp;
+    let mut amount = ctx.accounts.non_transferable_project.apply_pending_mint_price(now);
+    let mut tier = 0;
+    if let Some(tier_account) = ctx.accounts.tier.as_ref() {
+        // a sold out tier must not take the payment, `verify` would reject the DID
+        tier_account.check_open()?;
+        amount = tier_account.mint_price;
+        tier = tier_account.tier;
+    }
+    if let Some(voucher) = voucher.as_ref() {
+        amount = redeem_voucher(&mut ctx, voucher, &rns_id, wallet, amount)?;
+    }
+    amount = amount.saturating_sub(credit);
 
-    /* check if the fee_recipient is correct */
-    if !ctx.accounts.fee_recipient.to_account_info().key.eq(&fee_recipient) {
-        return err!(ErrorCode::InvalidFeeRecipient);
+    /* protect the payer from a price change between signing and execution */
+    if let Some(max_price) = max_price {
+        require!(amount <= max_price, ErrorCode::PriceExceedsMax);
     }
 
     /* check if the payer (authority) has enough SOL to pay the mint cost */
@@ -75,33 +392,62 @@ pub fn handler(
         return err!(ErrorCode::InsufficientBalance);
     }
 
-    invoke(
-        &system_instruction::transfer(
-            &ctx.accounts.authority.key,
-            &ctx.accounts.fee_recipient.key,
-            amount,
-        ),
-        &[
-            ctx.accounts.authority.to_account_info(),
-            ctx.accounts.fee_recipient.clone(),
-            ctx.accounts.system_program.to_account_info()
-        ],
+    let mut reward = 0;
+    if let Some(referrer) = referrer {
+        reward = record_referral(&mut ctx, referrer, wallet, amount)?;
+    }
+
+    // Escrowed on the user status until `verify` pays the fee recipient and
+    // the referrer, or `cancel_authorization` refunds it.
+    transfer_lamports(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.non_transferable_user_status.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        amount,
     )?;
 
+    let ttl_slots = ctx.accounts.non_transferable_project.authorization_ttl_slots;
 
     let status = &mut ctx.accounts.non_transferable_user_status;
-    require!(!status.is_authorized, ErrorCode::LDIDHasAuthorized);
-
-    status.authority = *ctx.accounts.authority.key;
+    status.authority = wallet;
+    status.rns_id_hash = rns_id_hash(&rns_id);
+    status.version = ACCOUNT_VERSION;
     status.bump = *ctx.bumps.get("non_transferable_user_status").unwrap();
     status.is_authorized = true;
+    status.payer = payer;
+    status.paid_amount = kept_payment + amount - reward;
+    if let Some(referrer) = referrer {
+        status.referrer = referrer;
+    } else if kept_reward == 0 {
+        status.referrer = Pubkey::default();
+    }
+    status.referral_reward = kept_reward + reward;
+    status.authorization_expiry_slot = if ttl_slots == 0 { 0 } else { slot + ttl_slots };
+    status.tier = tier;
+    // re-checked by `airdrop` and `verify` against the denylist of the time
+    status.jurisdiction = jurisdiction.unwrap_or_default();
+
+    if payer != wallet {
+        let Some(receipt) = ctx.accounts.sponsor_receipt.as_mut() else {
+            return err!(ErrorCode::MissingSponsorReceipt);
+        };
+        receipt.sponsor = payer;
+        receipt.beneficiary = wallet;
+        receipt.user_status = status.key();
+        receipt.amount = amount;
+        receipt.timestamp = Clock::get()?.unix_timestamp;
+        receipt.version = ACCOUNT_VERSION;
+        receipt.bump = *ctx.bumps.get("sponsor_receipt").unwrap();
+    }
 
     emit!(AuthorizeMintEvent {
         rns_id: rns_id.clone(),
-        wallet: ctx.accounts.authority.key()
+        wallet,
+        payer,
+        amount,
     });
 
-    msg!("RNSAddressAuthorized:_rnsId:{};_wallet:{};", rns_id.clone(), ctx.accounts.authority.key() );
+    msg!("RNSAddressAuthorized:_rnsId:{};_wallet:{};_payer:{};", rns_id.clone(), wallet, payer);
 
     Ok(())
 }
@@ -1,10 +1,14 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
 
+use crate::error::ErrorCode;
 use crate::state::*;
-use anchor_lang::solana_program::sysvar::rent::Rent;
+use crate::utils::{burn_nft, burn_v1, BurnNft, BurnV1};
+use anchor_lang::solana_program::sysvar::{self, rent::Rent};
 use anchor_spl::associated_token::AssociatedToken;
 use mpl_bubblegum::state::metaplex_anchor::MplTokenMetadata;
-use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount, ThawAccount};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, ThawAccount};
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount, TokenStandard};
 
 #[derive(Accounts)]
 #[instruction(rns_id: String, wallet: Pubkey)]
@@ -46,6 +50,7 @@ pub struct BurnNonTransferableNft<'info> {
   #[account(
     mut,
     constraint = non_transferable_nft_status.authority == authority.key(),
+    constraint = non_transferable_nft_status.matches_rns_id(&rns_id) @ ErrorCode::RnsIsNotMatch,
     seeds = [
       NON_TRANSFERABLE_NFT_STATUS_PREFIX.as_ref(),
       non_transferable_nft_mint.key().as_ref()
@@ -54,6 +59,37 @@ pub struct BurnNonTransferableNft<'info> {
   )]
   pub non_transferable_nft_status: Box<Account<'info, NftStatusAccount>>,
 
+  #[account(
+    init_if_needed,
+    payer = authority,
+    space = NON_TRANSFERABLE_WALLET_INDEX_SIZE,
+    seeds = [
+      NON_TRANSFERABLE_WALLET_INDEX_PREFIX.as_ref(),
+      authority.key().as_ref()
+    ],
+    bump
+  )]
+  pub non_transferable_wallet_index: Box<Account<'info, WalletIndexAccount>>,
+
+  /// Only DIDs that got an attributes record have one to keep in sync.
+  #[account(
+    mut,
+    seeds = [
+      NON_TRANSFERABLE_NFT_ATTRIBUTES_PREFIX.as_ref(),
+      non_transferable_nft_mint.key().as_ref()
+    ],
+    bump = non_transferable_nft_attributes.bump
+  )]
+  pub non_transferable_nft_attributes: Option<Box<Account<'info, NftAttributesAccount>>>,
+
+  /// CHECK: Receives the rent of the NFT accounts, checked against the issuance payer
+  #[account(mut, address = non_transferable_nft_status.payer)]
+  pub nft_rent_payer: AccountInfo<'info>,
+
+  /// CHECK: Receives the rent of the user status, checked against its payer
+  #[account(mut, address = non_transferable_user_status.payer)]
+  pub user_status_rent_payer: AccountInfo<'info>,
+
   #[account(
     mut,
     seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_ref()],
@@ -108,6 +144,14 @@ pub struct BurnNonTransferableNft<'info> {
   )]
   pub non_transferable_nft_master_edition: AccountInfo<'info>,
 
+  /// CHECK: Only programmable DIDs have a token record, closed by token metadata
+  #[account(mut)]
+  pub non_transferable_nft_token_record: Option<UncheckedAccount<'info>>,
+
+  /// CHECK: Only used for programmable DIDs
+  #[account(address = sysvar::instructions::ID)]
+  pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
   pub token_metadata_program: Program<'info, MplTokenMetadata>,
   pub associated_token_program: Program<'info, AssociatedToken>,
   pub token_program: Program<'info, Token>,
@@ -115,14 +159,8 @@ pub struct BurnNonTransferableNft<'info> {
   pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn handler(ctx: Context<BurnNonTransferableNft>, rns_id: String, _wallet: Pubkey) -> Result<()> {
-    msg!("start burn ..");
-
-    let signer_seeds: &[&[u8]] = &[
-        NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(),
-        &[ctx.accounts.non_transferable_project.bump],
-    ];
-
+/// Thaws a DID frozen by the project and burns it.
+fn burn_frozen(ctx: &Context<BurnNonTransferableNft>, signer_seeds: &[&[u8]]) -> Result<()> {
     msg!("thaw_account");
     let cpi_accounts = ThawAccount {
         account: ctx.accounts.user_token_account.to_account_info(),
@@ -136,25 +174,106 @@ pub fn handler(ctx: Context<BurnNonTransferableNft>, rns_id: String, _wallet: Pu
     token::thaw_account(cpi_ctx.with_signer(&[&signer_seeds[..]]))?;
 
     msg!("burn_nft");
-    let cpi_accounts = Burn {
-        authority: ctx.accounts.authority.to_account_info(),
-        from: ctx.accounts.user_token_account.to_account_info(),
+    let cpi_accounts = BurnNft {
+        metadata: ctx.accounts.non_transferable_nft_metadata.to_account_info(),
+        owner: ctx.accounts.authority.to_account_info(),
         mint: ctx.accounts.non_transferable_nft_mint.to_account_info(),
+        token_account: ctx.accounts.user_token_account.to_account_info(),
+        master_edition: ctx.accounts.non_transferable_nft_master_edition.to_account_info(),
+        spl_token: ctx.accounts.token_program.to_account_info(),
+        collection_metadata: ctx.accounts.non_transferable_project_metadata.to_account_info(),
     };
 
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let burn_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    let cpi_program = ctx.accounts.token_metadata_program.to_account_info();
+    burn_nft(CpiContext::new(cpi_program, cpi_accounts))?;
+
+    Ok(())
+}
 
-    token::burn(burn_ctx, 1)?;
+pub fn handler(ctx: Context<BurnNonTransferableNft>, rns_id: String, _wallet: Pubkey) -> Result<()> {
+    msg!("start burn ..");
+
+    let signer_seeds: &[&[u8]] = &[
+        NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(),
+        &[ctx.accounts.non_transferable_project.bump],
+    ];
+
+    let programmable = matches!(
+        Metadata::from_account_info(&ctx.accounts.non_transferable_nft_metadata)?.token_standard,
+        Some(TokenStandard::ProgrammableNonFungible)
+    );
+
+    // Metaplex sends the rent of the closed accounts to the owner, who hands it
+    // back to whoever paid for the issuance.
+    let mut reclaimed_rent = ctx.accounts.user_token_account.to_account_info().lamports()
+        + ctx.accounts.non_transferable_nft_metadata.lamports()
+        + ctx.accounts.non_transferable_nft_master_edition.lamports();
+
+    if programmable {
+        let (Some(token_record), Some(sysvar_instructions)) = (
+            ctx.accounts.non_transferable_nft_token_record.as_ref(),
+            ctx.accounts.sysvar_instructions.as_ref(),
+        ) else {
+            return err!(ErrorCode::MissingProgrammableAccounts);
+        };
+        reclaimed_rent += token_record.lamports();
+
+        let cpi_accounts = BurnV1 {
+            authority: ctx.accounts.authority.to_account_info(),
+            collection_metadata: ctx.accounts.non_transferable_project_metadata.to_account_info(),
+            metadata: ctx.accounts.non_transferable_nft_metadata.to_account_info(),
+            edition: ctx.accounts.non_transferable_nft_master_edition.to_account_info(),
+            mint: ctx.accounts.non_transferable_nft_mint.to_account_info(),
+            token: ctx.accounts.user_token_account.to_account_info(),
+            token_record: token_record.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            sysvar_instructions: sysvar_instructions.to_account_info(),
+            spl_token_program: ctx.accounts.token_program.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_metadata_program.to_account_info();
+        burn_v1(CpiContext::new(cpi_program, cpi_accounts))?;
+    } else {
+        burn_frozen(&ctx, signer_seeds)?;
+    }
+
+    if ctx.accounts.nft_rent_payer.key() != ctx.accounts.authority.key() {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.authority.to_account_info(),
+            to: ctx.accounts.nft_rent_payer.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.system_program.to_account_info();
+        system_program::transfer(CpiContext::new(cpi_program, cpi_accounts), reclaimed_rent)?;
+    }
 
     // 更新状态
     let nft_status = &mut ctx.accounts.non_transferable_nft_status;
-    nft_status.merkle_root = String::new();
-    nft_status.rns_id = String::new();
+    if nft_status.is_active() {
+        // a revoked DID no longer counts towards the rns_id limit
+        let rns_id_status = &mut ctx.accounts.non_transferable_rns_id_status;
+        rns_id_status.num = rns_id_status.num.saturating_sub(1);
+    }
+    nft_status.merkle_root = [0; 32];
+    nft_status.is_burned = true;
+
+    if let Some(attributes) = ctx.accounts.non_transferable_nft_attributes.as_mut() {
+        attributes.status = DidStatus::Burned;
+    }
+
+    let wallet_index = &mut ctx.accounts.non_transferable_wallet_index;
+    wallet_index.version = ACCOUNT_VERSION;
+    wallet_index.wallet = ctx.accounts.authority.key();
+    wallet_index.bump = *ctx.bumps.get("non_transferable_wallet_index").unwrap();
+    wallet_index.upsert(
+        rns_id_hash(&rns_id),
+        ctx.accounts.non_transferable_nft_mint.key(),
+        DidStatus::Burned,
+    )?;
 
-    let user_status = &mut ctx.accounts.non_transferable_user_status;
-    user_status.is_authorized = false;
-    user_status.is_minted = false;
+    // The user status is closed so a later authorization starts from scratch.
+    ctx.accounts
+        .non_transferable_user_status
+        .close(ctx.accounts.user_status_rent_payer.to_account_info())?;
 
     msg!(
         "RNSBurnID:_rnsId:{};_wallet:{};_tokenId:{}",
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::state::*;
+
+#[event]
+pub struct CancelAuthorizationEvent {
+  pub rns_id: String,
+  pub wallet: Pubkey,
+  pub cancelled_by: Pubkey,
+  pub refund: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(rns_id: String, wallet: Pubkey)]
+pub struct CancelAuthorizationContext<'info> {
+  /// Either the project authority or the wallet the authorization belongs to.
+  pub authority: Signer<'info>,
+
+  #[account(
+    seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_ref()],
+    bump = non_transferable_project.bump
+  )]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+  #[account(
+    mut,
+    constraint = authority.key() == non_transferable_user_status.authority
+      || authority.key() == non_transferable_project.authority @ ErrorCode::InvalidAuthority,
+    seeds = [
+      NON_TRANSFERABLE_NFT_USERSTATUS_PREFIX.as_ref(),
+      &hash_seed(&rns_id)[..32],
+      wallet.key().as_ref()
+    ],
+    bump = non_transferable_user_status.bump
+  )]
+  pub non_transferable_user_status: Box<Account<'info, UserStatusAccount>>,
+
+  /// CHECK: Only receives the refund, checked against the recorded payer
+  #[account(mut, address = non_transferable_user_status.payer)]
+  pub payer: AccountInfo<'info>,
+}
+
+pub fn handler(ctx: Context<CancelAuthorizationContext>, rns_id: String, wallet: Pubkey) -> Result<()> {
+  let status = &mut ctx.accounts.non_transferable_user_status;
+  require!(status.is_authorized && !status.is_minted, ErrorCode::NotAuthorized);
+
+  // The payment is still escrowed on the user status, a program account, so
+  // it is refunded without the fee recipient.
+  let refund = status.paid_amount + status.referral_reward;
+  **status.to_account_info().try_borrow_mut_lamports()? -= refund;
+  **ctx.accounts.payer.try_borrow_mut_lamports()? += refund;
+
+  status.is_authorized = false;
+  status.paid_amount = 0;
+  status.referral_reward = 0;
+  status.referrer = Pubkey::default();
+  status.authorization_expiry_slot = 0;
+
+  emit!(CancelAuthorizationEvent {
+    rns_id: rns_id.clone(),
+    wallet,
+    cancelled_by: ctx.accounts.authority.key(),
+    refund,
+  });
+
+  msg!(
+    "RNSAuthorizationCancelled:_rnsId:{};_wallet:{};_refund:{}",
+    rns_id,
+    wallet,
+    refund
+  );
+
+  Ok(())
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::state::*;
+
+#[event]
+pub struct ReferralRewardsClaimedEvent {
+  pub referrer: Pubkey,
+  pub amount: u64,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralRewardsContext<'info> {
+  #[account(mut)]
+  pub referrer: Signer<'info>,
+
+  #[account(
+    mut,
+    has_one = referrer,
+    seeds = [
+      NON_TRANSFERABLE_REFERRAL_PREFIX.as_ref(),
+      referrer.key().as_ref()
+    ],
+    bump = referral_stats.bump
+  )]
+  pub referral_stats: Box<Account<'info, ReferralStatsAccount>>,
+}
+
+pub fn handler(ctx: Context<ClaimReferralRewardsContext>) -> Result<()> {
+  let stats = &mut ctx.accounts.referral_stats;
+  let amount = stats.pending_rewards;
+  require!(amount > 0, ErrorCode::NoReferralRewards);
+
+  stats.pending_rewards = 0;
+
+  // The stats PDA is owned by this program, so its escrowed lamports can be moved directly.
+  **stats.to_account_info().try_borrow_mut_lamports()? -= amount;
+  **ctx.accounts.referrer.to_account_info().try_borrow_mut_lamports()? += amount;
+
+  emit!(ReferralRewardsClaimedEvent {
+    referrer: ctx.accounts.referrer.key(),
+    amount,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::state::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct CampaignArgs {
+  pub kind: VoucherKind,
+  pub issuer: Pubkey,
+  pub merkle_root: [u8; 32],
+  pub discount_kind: DiscountKind,
+  pub discount_value: u64,
+  pub max_uses: u64,
+  pub is_active: bool,
+}
+
+impl CampaignArgs {
+  pub fn apply(&self, campaign: &mut CampaignAccount) -> Result<()> {
+    if self.discount_kind == DiscountKind::Percentage {
+      require!(self.discount_value <= MAX_DISCOUNT_BPS, ErrorCode::InvalidDiscount);
+    }
+
+    campaign.kind = self.kind;
+    campaign.issuer = self.issuer;
+    campaign.merkle_root = self.merkle_root;
+    campaign.discount_kind = self.discount_kind;
+    campaign.discount_value = self.discount_value;
+    campaign.max_uses = self.max_uses;
+    campaign.is_active = self.is_active;
+    Ok(())
+  }
+}
+
+#[event]
+pub struct CampaignEvent {
+  pub campaign_id: u64,
+  pub discount_kind: DiscountKind,
+  pub discount_value: u64,
+  pub max_uses: u64,
+  pub is_active: bool,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: u64)]
+pub struct CreateCampaignContext<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+
+  #[account(
+    has_one = authority,
+    seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_ref()],
+    bump = non_transferable_project.bump
+  )]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+  #[account(
+    init,
+    payer = authority,
+    space = NON_TRANSFERABLE_CAMPAIGN_SIZE,
+    seeds = [
+      NON_TRANSFERABLE_CAMPAIGN_PREFIX.as_ref(),
+      campaign_id.to_le_bytes().as_ref()
+    ],
+    bump
+  )]
+  pub campaign: Box<Account<'info, CampaignAccount>>,
+
+  pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CreateCampaignContext>, campaign_id: u64, args: CampaignArgs) -> Result<()> {
+  let campaign = &mut ctx.accounts.campaign;
+
+  campaign.campaign_id = campaign_id;
+  campaign.version = ACCOUNT_VERSION;
+  campaign.bump = *ctx.bumps.get("campaign").unwrap();
+  args.apply(campaign)?;
+
+  emit!(CampaignEvent {
+    campaign_id,
+    discount_kind: campaign.discount_kind,
+    discount_value: campaign.discount_value,
+    max_uses: campaign.max_uses,
+    is_active: campaign.is_active,
+  });
+
+  Ok(())
+}
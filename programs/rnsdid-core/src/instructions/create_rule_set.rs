@@ -0,0 +1,122 @@
+use anchor_lang::{
+  prelude::*,
+  solana_program::{program::invoke, system_instruction},
+};
+use mpl_token_auth_rules::{
+  pda::find_rule_set_address,
+  state::{Rule, RuleSetRevisionMapV1, RuleSetV1, RULE_SET_SERIALIZED_HEADER_LEN},
+};
+
+use crate::error::ErrorCode;
+use crate::state::*;
+use crate::utils::{create_or_update_rule_set, CreateOrUpdateRuleSet};
+
+#[event]
+pub struct CreateRuleSetEvent {
+  pub rule_set: Pubkey,
+}
+
+/// Creates the RuleSet programmable DIDs are issued under, owned by the project
+/// PDA. It denies every transfer, only token metadata's migration delegate can
+/// still move a DID, so non-transferability no longer depends on a freeze.
+#[derive(Accounts)]
+pub struct CreateRuleSetContext<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+
+  #[account(
+    mut,
+    has_one = authority,
+    seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_ref()],
+    bump = non_transferable_project.bump
+  )]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+  /// CHECK: Created by token-auth-rules, address checked in the handler
+  #[account(mut)]
+  pub rule_set: UncheckedAccount<'info>,
+
+  /// CHECK: Checked by address
+  #[account(address = mpl_token_auth_rules::ID)]
+  pub authorization_rules_program: UncheckedAccount<'info>,
+
+  pub system_program: Program<'info, System>,
+}
+
+fn deny_all_transfers(owner: Pubkey) -> Result<RuleSetV1> {
+  let mut rule_set = RuleSetV1::new(NON_TRANSFERABLE_RULE_SET_NAME.to_string(), owner);
+  let deny = Rule::Not {
+    rule: Box::new(Rule::Pass),
+  };
+  for operation in [
+    "Transfer:Owner",
+    "Transfer:WalletToWallet",
+    "Transfer:TransferDelegate",
+    "Transfer:SaleDelegate",
+  ] {
+    rule_set
+      .add(operation.to_string(), deny.clone())
+      .map_err(|_| ErrorCode::InvalidDataProvided)?;
+  }
+  rule_set
+    .add("Transfer:MigrationDelegate".to_string(), Rule::Pass)
+    .map_err(|_| ErrorCode::InvalidDataProvided)?;
+  Ok(rule_set)
+}
+
+pub fn handler(ctx: Context<CreateRuleSetContext>) -> Result<()> {
+  let project = &ctx.accounts.non_transferable_project;
+  require!(project.rule_set == Pubkey::default(), ErrorCode::RuleSetAlreadyCreated);
+
+  let (rule_set_key, _) =
+    find_rule_set_address(project.key(), NON_TRANSFERABLE_RULE_SET_NAME.to_string());
+  require_keys_eq!(ctx.accounts.rule_set.key(), rule_set_key, ErrorCode::InvalidDataProvided);
+
+  let serialized_rule_set = rmp_serde::to_vec(&deny_all_transfers(project.key())?)
+    .map_err(|_| ErrorCode::InvalidDataProvided)?;
+
+  // The project PDA holds data, so it cannot pay for the RuleSet itself. Funding
+  // the account beforehand leaves token-auth-rules nothing to transfer.
+  let mut revision_map = RuleSetRevisionMapV1::default();
+  revision_map.rule_set_revisions.push(RULE_SET_SERIALIZED_HEADER_LEN);
+  let space = RULE_SET_SERIALIZED_HEADER_LEN
+    + 2 // lib and revision map versions
+    + revision_map.try_to_vec()?.len()
+    + serialized_rule_set.len();
+  let rent = Rent::get()?.minimum_balance(space);
+  let rule_set = ctx.accounts.rule_set.to_account_info();
+  if rule_set.lamports() < rent {
+    invoke(
+      &system_instruction::transfer(
+        ctx.accounts.authority.key,
+        rule_set.key,
+        rent - rule_set.lamports(),
+      ),
+      &[
+        ctx.accounts.authority.to_account_info(),
+        rule_set.clone(),
+        ctx.accounts.system_program.to_account_info(),
+      ],
+    )?;
+  }
+
+  let project_signer_seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &[project.bump]];
+  let cpi_accounts = CreateOrUpdateRuleSet {
+    payer: project.to_account_info(),
+    rule_set_pda: rule_set,
+    system_program: ctx.accounts.system_program.to_account_info(),
+  };
+  create_or_update_rule_set(
+    CpiContext::new(ctx.accounts.authorization_rules_program.to_account_info(), cpi_accounts)
+      .with_signer(&[&project_signer_seeds[..]]),
+    serialized_rule_set,
+  )?;
+
+  ctx.accounts.non_transferable_project.rule_set = rule_set_key;
+
+  emit!(CreateRuleSetEvent {
+    rule_set: rule_set_key,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,221 @@
+use crate::utils::{
+  create_master_edition_v3, create_metadata_accounts_v3, CreateMasterEditionV3,
+  CreateMetadataAccountsV3,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
+use mpl_bubblegum::state::metaplex_anchor::MplTokenMetadata;
+use mpl_token_metadata::state::{CollectionDetails, DataV2, MAX_URI_LENGTH};
+
+use crate::error::ErrorCode;
+use crate::state::*;
+
+/// Metadata of the tier collection NFT.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct TierCollectionArgs {
+  pub name: String,
+  pub symbol: String,
+  pub uri: String,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct TierArgs {
+  pub base_uri: String,
+  pub mint_price: u64,
+  pub max_supply: u64,
+  pub is_active: bool,
+}
+
+impl TierArgs {
+  pub fn apply(&self, tier: &mut TierAccount) -> Result<()> {
+    require!(self.base_uri.len() <= MAX_URI_LENGTH, ErrorCode::UriTooLong);
+
+    tier.base_uri = self.base_uri.clone();
+    tier.mint_price = self.mint_price;
+    tier.max_supply = self.max_supply;
+    tier.is_active = self.is_active;
+    Ok(())
+  }
+}
+
+#[event]
+pub struct TierEvent {
+  pub tier: u8,
+  pub mint: Pubkey,
+  pub mint_price: u64,
+  pub max_supply: u64,
+  pub is_active: bool,
+}
+
+#[derive(Accounts)]
+#[instruction(tier: u8)]
+pub struct CreateTierContext<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+
+  #[account(
+    has_one = authority,
+    seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_ref()],
+    bump = non_transferable_project.bump
+  )]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+  #[account(
+    init,
+    payer = authority,
+    space = NON_TRANSFERABLE_TIER_SIZE,
+    seeds = [NON_TRANSFERABLE_TIER_PREFIX.as_ref(), tier.to_le_bytes().as_ref()],
+    bump
+  )]
+  pub tier_account: Box<Account<'info, TierAccount>>,
+
+  #[account(
+    init,
+    payer = authority,
+    seeds = [NON_TRANSFERABLE_TIER_MINT_PREFIX.as_ref(), tier.to_le_bytes().as_ref()],
+    bump,
+    mint::decimals = 0,
+    mint::authority = non_transferable_project,
+    mint::freeze_authority = non_transferable_project
+  )]
+  pub tier_mint: Box<Account<'info, Mint>>,
+
+  #[account(
+    init,
+    payer = authority,
+    seeds = [NON_TRANSFERABLE_TIER_VAULT_PREFIX.as_ref(), tier.to_le_bytes().as_ref()],
+    bump,
+    token::mint = tier_mint,
+    token::authority = non_transferable_project,
+  )]
+  pub tier_vault: Box<Account<'info, TokenAccount>>,
+
+  /// CHECK: Used in CPI So no Harm
+  #[account(mut)]
+  pub tier_metadata: AccountInfo<'info>,
+
+  /// CHECK: Used in CPI So no Harm
+  #[account(mut)]
+  pub tier_master_edition: AccountInfo<'info>,
+
+  pub token_metadata_program: Program<'info, MplTokenMetadata>,
+  pub token_program: Program<'info, Token>,
+  pub system_program: Program<'info, System>,
+  pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> CreateTierContext<'info> {
+  fn create_metadata_accounts_ctx(
+    &self,
+  ) -> CpiContext<'_, '_, '_, 'info, CreateMetadataAccountsV3<'info>> {
+    let cpi_accounts = CreateMetadataAccountsV3 {
+      metadata: self.tier_metadata.to_account_info(),
+      mint: self.tier_mint.to_account_info(),
+      mint_authority: self.non_transferable_project.to_account_info(),
+      payer: self.authority.to_account_info(),
+      update_authority: self.non_transferable_project.to_account_info(),
+      system_program: self.system_program.to_account_info(),
+      rent: self.rent.to_account_info(),
+    };
+    CpiContext::new(self.token_metadata_program.to_account_info(), cpi_accounts)
+  }
+
+  fn create_master_edition_ctx(
+    &self,
+  ) -> CpiContext<'_, '_, '_, 'info, CreateMasterEditionV3<'info>> {
+    let cpi_accounts = CreateMasterEditionV3 {
+      metadata: self.tier_metadata.to_account_info(),
+      edition: self.tier_master_edition.to_account_info(),
+      mint: self.tier_mint.to_account_info(),
+      mint_authority: self.non_transferable_project.to_account_info(),
+      payer: self.authority.to_account_info(),
+      update_authority: self.non_transferable_project.to_account_info(),
+      system_program: self.system_program.to_account_info(),
+      rent: self.rent.to_account_info(),
+      token_program: self.token_program.to_account_info(),
+    };
+    CpiContext::new(self.token_metadata_program.to_account_info(), cpi_accounts)
+  }
+
+  fn mint_to_ctx(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+    let cpi_accounts = MintTo {
+      mint: self.tier_mint.to_account_info(),
+      to: self.tier_vault.to_account_info(),
+      authority: self.non_transferable_project.to_account_info(),
+    };
+    CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+  }
+}
+
+pub fn handler(
+  ctx: Context<CreateTierContext>,
+  tier: u8,
+  collection: TierCollectionArgs,
+  args: TierArgs,
+) -> Result<()> {
+  require!(tier != 0, ErrorCode::InvalidTierNumber);
+
+  let tier_account = &mut ctx.accounts.tier_account;
+  tier_account.version = ACCOUNT_VERSION;
+  tier_account.tier = tier;
+  tier_account.mint = ctx.accounts.tier_mint.key();
+  tier_account.mint_bump = *ctx.bumps.get("tier_mint").unwrap();
+  tier_account.bump = *ctx.bumps.get("tier_account").unwrap();
+  args.apply(tier_account)?;
+
+  let project_signer_seeds = [
+    NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(),
+    &[ctx.accounts.non_transferable_project.bump],
+  ];
+
+  token::mint_to(
+    ctx
+      .accounts
+      .mint_to_ctx()
+      .with_signer(&[&project_signer_seeds[..]]),
+    1,
+  )?;
+
+  let data = DataV2 {
+    name: collection.name,
+    symbol: collection.symbol,
+    uri: collection.uri,
+    seller_fee_basis_points: 0,
+    creators: None,
+    collection: None,
+    uses: None,
+  };
+
+  #[allow(deprecated)]
+  let details = CollectionDetails::V1 { size: 0 };
+
+  create_metadata_accounts_v3(
+    ctx
+      .accounts
+      .create_metadata_accounts_ctx()
+      .with_signer(&[&project_signer_seeds[..]]),
+    data,
+    true,
+    true,
+    Some(details),
+  )?;
+
+  create_master_edition_v3(
+    ctx
+      .accounts
+      .create_master_edition_ctx()
+      .with_signer(&[&project_signer_seeds[..]]),
+    Some(0),
+  )?;
+
+  let tier_account = &ctx.accounts.tier_account;
+  emit!(TierEvent {
+    tier,
+    mint: tier_account.mint,
+    mint_price: tier_account.mint_price,
+    max_supply: tier_account.max_supply,
+    is_active: tier_account.is_active,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,242 @@
+use anchor_lang::{
+  prelude::*,
+  solana_program::sysvar::{
+    self,
+    instructions::{load_current_index_checked, load_instruction_at_checked},
+  },
+  Discriminator,
+};
+use anchor_spl::token::{self, Mint, ThawAccount, Token, TokenAccount};
+use mpl_bubblegum::state::metaplex_anchor::MplTokenMetadata;
+
+use crate::error::ErrorCode;
+use crate::instruction::Reissue;
+use crate::state::*;
+use crate::utils::{burn_nft, BurnNft};
+
+#[event]
+pub struct ExchangeV1DidEvent {
+  pub rns_id: String,
+  pub wallet: Pubkey,
+  pub v1_mint: Pubkey,
+}
+
+/// Burns a DID of the v1 collection so the same rns_id can be issued again on
+/// the current one. The transaction must go on with `airdrop`, `verify` and a
+/// `reissue` linking the new DID to the v1 one, which is why the project
+/// authority co-signs it. See `require_reissue`.
+///
+/// The v1 statuses must have been moved to the current layout with
+/// `migrate_account` first.
+#[derive(Accounts)]
+#[instruction(rns_id: String, wallet: Pubkey)]
+pub struct ExchangeV1DidContext<'info> {
+  #[account(mut, address = wallet)]
+  pub holder: Signer<'info>,
+
+  #[account(mut)]
+  pub authority: Signer<'info>,
+
+  #[account(
+    constraint = non_transferable_project.authority == authority.key(),
+    seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_ref()],
+    bump = non_transferable_project.bump
+  )]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+  /// CHECK: Only signs as the freeze authority of the v1 tokens
+  #[account(
+    seeds = [NON_TRANSFERABLE_PROJECT_V1_PREFIX.as_ref()],
+    bump
+  )]
+  pub v1_project: UncheckedAccount<'info>,
+
+  #[account(
+    mut,
+    constraint = v1_nft_mint.freeze_authority == Some(v1_project.key()).into() @ ErrorCode::NotV1Did,
+  )]
+  pub v1_nft_mint: Box<Account<'info, Mint>>,
+
+  #[account(
+    mut,
+    associated_token::mint = v1_nft_mint,
+    associated_token::authority = holder,
+  )]
+  pub v1_token_account: Box<Account<'info, TokenAccount>>,
+
+  /// CHECK: Used in CPI
+  #[account(
+    mut,
+    seeds = [
+      METADATA.as_ref(),
+      token_metadata_program.key().as_ref(),
+      v1_nft_mint.key().as_ref()
+    ],
+    seeds::program = token_metadata_program.key(),
+    bump,
+  )]
+  pub v1_nft_metadata: UncheckedAccount<'info>,
+
+  /// CHECK: Used in CPI
+  #[account(
+    mut,
+    seeds = [
+      METADATA.as_ref(),
+      token_metadata_program.key().as_ref(),
+      v1_nft_mint.key().as_ref(),
+      "edition".as_ref()
+    ],
+    seeds::program = token_metadata_program.key(),
+    bump,
+  )]
+  pub v1_nft_master_edition: UncheckedAccount<'info>,
+
+  /// CHECK: Metadata of the v1 collection, checked by token metadata
+  #[account(mut)]
+  pub v1_collection_metadata: UncheckedAccount<'info>,
+
+  #[account(
+    mut,
+    constraint = v1_nft_status.authority == wallet,
+    constraint = v1_nft_status.matches_rns_id(&rns_id) @ ErrorCode::RnsIsNotMatch,
+    seeds = [
+      NON_TRANSFERABLE_NFT_STATUS_PREFIX.as_ref(),
+      v1_nft_mint.key().as_ref()
+    ],
+    bump = v1_nft_status.bump
+  )]
+  pub v1_nft_status: Box<Account<'info, NftStatusAccount>>,
+
+  #[account(
+    mut,
+    seeds = [
+      NON_TRANSFERABLE_NFT_USERSTATUS_PREFIX.as_ref(),
+      &hash_seed(&rns_id)[..32],
+      wallet.key().as_ref()
+    ],
+    bump = non_transferable_user_status.bump
+  )]
+  pub non_transferable_user_status: Box<Account<'info, UserStatusAccount>>,
+
+  #[account(
+    mut,
+    seeds = [
+      NON_TRANSFERABLE_NFT_RNSID_PREFIX.as_ref(),
+      &hash_seed(&rns_id)[..32],
+    ],
+    bump
+  )]
+  pub non_transferable_rns_id_status: Box<Account<'info, RnsIdStatusAccount>>,
+
+  #[account(
+    init_if_needed,
+    payer = authority,
+    space = NON_TRANSFERABLE_WALLET_INDEX_SIZE,
+    seeds = [
+      NON_TRANSFERABLE_WALLET_INDEX_PREFIX.as_ref(),
+      wallet.key().as_ref()
+    ],
+    bump
+  )]
+  pub non_transferable_wallet_index: Box<Account<'info, WalletIndexAccount>>,
+
+  /// CHECK: Only read to find the paired `reissue`
+  #[account(address = sysvar::instructions::ID)]
+  pub instructions: UncheckedAccount<'info>,
+
+  pub token_metadata_program: Program<'info, MplTokenMetadata>,
+  pub token_program: Program<'info, Token>,
+  pub system_program: Program<'info, System>,
+}
+
+/// Requires a later instruction of the transaction to `reissue` a DID in place
+/// of `v1_nft_status`. `reissue` only links a live DID of the same rns_id and
+/// wallet, so the exchange can't leave the holder without one.
+fn require_reissue(
+  instructions: &AccountInfo,
+  v1_nft_status: &Pubkey,
+  rns_id: &str,
+  wallet: &Pubkey,
+) -> Result<()> {
+  let mut index = load_current_index_checked(instructions)? as usize + 1;
+  while let Ok(ix) = load_instruction_at_checked(index, instructions) {
+    index += 1;
+    // `previous_nft_status` is the third account of `ReissueContext`
+    if ix.program_id != crate::ID
+      || !ix.data.starts_with(&Reissue::DISCRIMINATOR)
+      || ix.accounts.get(2).map(|meta| meta.pubkey) != Some(*v1_nft_status)
+    {
+      continue;
+    }
+    let args = Reissue::deserialize(&mut &ix.data[8..])?;
+    if args.rns_id == rns_id && args.wallet == *wallet {
+      return Ok(());
+    }
+  }
+  err!(ErrorCode::MissingReissue)
+}
+
+pub fn handler(ctx: Context<ExchangeV1DidContext>, rns_id: String, wallet: Pubkey) -> Result<()> {
+  require!(ctx.accounts.v1_nft_status.is_active(), ErrorCode::DidNotActive);
+  require_reissue(
+    &ctx.accounts.instructions,
+    &ctx.accounts.v1_nft_status.key(),
+    &rns_id,
+    &wallet,
+  )?;
+
+  let v1_bump = [*ctx.bumps.get("v1_project").unwrap()];
+  let v1_signer_seeds: &[&[u8]] = &[NON_TRANSFERABLE_PROJECT_V1_PREFIX.as_bytes(), &v1_bump];
+
+  let cpi_accounts = ThawAccount {
+    account: ctx.accounts.v1_token_account.to_account_info(),
+    mint: ctx.accounts.v1_nft_mint.to_account_info(),
+    authority: ctx.accounts.v1_project.to_account_info(),
+  };
+  let cpi_program = ctx.accounts.token_program.to_account_info();
+  token::thaw_account(CpiContext::new(cpi_program, cpi_accounts).with_signer(&[v1_signer_seeds]))?;
+
+  // Also closes the metadata and edition, returning their rent to the holder.
+  let cpi_accounts = BurnNft {
+    metadata: ctx.accounts.v1_nft_metadata.to_account_info(),
+    owner: ctx.accounts.holder.to_account_info(),
+    mint: ctx.accounts.v1_nft_mint.to_account_info(),
+    token_account: ctx.accounts.v1_token_account.to_account_info(),
+    master_edition: ctx.accounts.v1_nft_master_edition.to_account_info(),
+    spl_token: ctx.accounts.token_program.to_account_info(),
+    collection_metadata: ctx.accounts.v1_collection_metadata.to_account_info(),
+  };
+  let cpi_program = ctx.accounts.token_metadata_program.to_account_info();
+  burn_nft(CpiContext::new(cpi_program, cpi_accounts))?;
+
+  let v1_nft_status = &mut ctx.accounts.v1_nft_status;
+  v1_nft_status.merkle_root = [0; 32];
+  v1_nft_status.is_burned = true;
+
+  let rns_id_status = &mut ctx.accounts.non_transferable_rns_id_status;
+  rns_id_status.num = rns_id_status.num.saturating_sub(1);
+
+  // lets `verify` issue the current-collection DID to the same wallet
+  ctx.accounts.non_transferable_user_status.is_minted = false;
+
+  let wallet_index = &mut ctx.accounts.non_transferable_wallet_index;
+  wallet_index.version = ACCOUNT_VERSION;
+  wallet_index.wallet = wallet;
+  wallet_index.bump = *ctx.bumps.get("non_transferable_wallet_index").unwrap();
+  wallet_index.upsert(rns_id_hash(&rns_id), v1_nft_status.mint, DidStatus::Burned)?;
+
+  msg!(
+    "RNSExchangeV1ID:_rnsId:{};_wallet:{};_tokenId:{}",
+    rns_id,
+    wallet,
+    v1_nft_status.mint
+  );
+
+  emit!(ExchangeV1DidEvent {
+    rns_id: rns_id.clone(),
+    wallet,
+    v1_mint: v1_nft_status.mint,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::instructions::migrate_account::{read_unversioned, LEGACY_PROJECT_SIZE};
+use crate::state::*;
+
+#[event]
+pub struct ImportV1ProjectEvent {
+  pub v1_project: Pubkey,
+  pub mint_price: u64,
+  pub fee_recipient: Pubkey,
+  pub blocked_addresses: u32,
+  pub blocked_rns_ids: u32,
+}
+
+/// Copies the price, fee recipient and blocklists of the v1 project into the
+/// current one. Blocklist entries are merged, the v1 entry wins on conflicts.
+#[derive(Accounts)]
+pub struct ImportV1ProjectContext<'info> {
+  pub authority: Signer<'info>,
+
+  #[account(
+    mut,
+    constraint = non_transferable_project.authority == authority.key(),
+    seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_ref()],
+    bump = non_transferable_project.bump
+  )]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+  /// CHECK: Written before accounts were versioned, read by `read_unversioned`
+  #[account(
+    owner = crate::ID,
+    seeds = [NON_TRANSFERABLE_PROJECT_V1_PREFIX.as_ref()],
+    bump
+  )]
+  pub v1_project: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<ImportV1ProjectContext>) -> Result<()> {
+  // v1 was deployed from the layout this program started from. The size, the
+  // discriminator checked by `read_unversioned` and the bump confirm it.
+  let data = ctx.accounts.v1_project.try_borrow_data()?;
+  require!(data.len() == LEGACY_PROJECT_SIZE, ErrorCode::InvalidLegacyAccount);
+  let v1: ProjectAccount = read_unversioned(&data, NON_TRANSFERABLE_PROJECT_SIZE)?;
+  require!(
+    v1.bump == *ctx.bumps.get("v1_project").unwrap(),
+    ErrorCode::InvalidLegacyAccount
+  );
+  require_keys_eq!(v1.authority, ctx.accounts.authority.key(), ErrorCode::InvalidAuthority);
+
+  let project = &mut ctx.accounts.non_transferable_project;
+  project.mint_price = v1.mint_price;
+  project.fee_recipient = v1.fee_recipient;
+  for pair in v1.is_blocked_address.iter() {
+    project.set_blocked_address(pair.key, pair.value);
+  }
+  for pair in v1.is_blocked_rns_id.iter() {
+    project.set_blocked_rns_id(pair.key.clone(), pair.value);
+  }
+
+  emit!(ImportV1ProjectEvent {
+    v1_project: ctx.accounts.v1_project.key(),
+    mint_price: v1.mint_price,
+    fee_recipient: v1.fee_recipient,
+    blocked_addresses: v1.is_blocked_address.len() as u32,
+    blocked_rns_ids: v1.is_blocked_rns_id.len() as u32,
+  });
+
+  Ok(())
+}
@@ -8,7 +8,7 @@ use anchor_lang::prelude::*;
 use anchor_lang::solana_program::sysvar::rent::Rent;
 use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
 use mpl_bubblegum::state::metaplex_anchor::MplTokenMetadata;
-use mpl_token_metadata::state::DataV2;
+use mpl_token_metadata::state::{CollectionDetails, DataV2};
 
 use crate::state::*;
 
@@ -118,6 +118,7 @@ impl<'info> Initialize<'info> {
 pub fn handler(ctx: Context<Initialize>, args: InitializeArgs) -> Result<()> {
   let non_transferable_project = &mut ctx.accounts.non_transferable_project;
 
+  non_transferable_project.version = ACCOUNT_VERSION;
   non_transferable_project.mint_price = 100;
   non_transferable_project.authority = ctx.accounts.authority.to_account_info().key();
   non_transferable_project.bump = *ctx.bumps.get("non_transferable_project").unwrap();
@@ -154,6 +155,10 @@ pub fn handler(ctx: Context<Initialize>, args: InitializeArgs) -> Result<()> {
     uses: None,
   };
 
+  // sized, so explorers report the number of verified DIDs as the supply
+  #[allow(deprecated)]
+  let details = CollectionDetails::V1 { size: 0 };
+
   create_metadata_accounts_v3(
     ctx
       .accounts
@@ -162,7 +167,7 @@ pub fn handler(ctx: Context<Initialize>, args: InitializeArgs) -> Result<()> {
     data,
     true,
     true,
-    None,
+    Some(details),
   )?;
 
   create_master_edition_v3(
@@ -0,0 +1,273 @@
+use anchor_lang::{
+  prelude::*,
+  solana_program::{program::invoke, system_instruction},
+  Discriminator,
+};
+
+use crate::error::ErrorCode;
+use crate::state::*;
+use crate::utils::parse_merkle_root;
+
+#[event]
+pub struct MigrateAccountEvent {
+  pub account: Pubkey,
+  pub kind: AccountKind,
+  pub wallet: Pubkey,
+}
+
+/// `UserStatusAccount` as first deployed, before accounts were versioned.
+#[derive(AnchorDeserialize)]
+struct LegacyUserStatus {
+  authority: Pubkey,
+  rns_id: String,
+  is_minted: bool,
+  is_authorized: bool,
+  bump: u8,
+}
+
+const LEGACY_USER_STATUS_SIZE: usize = 8 +
+  32 + // authority
+  50 + // rns_id
+  1 +  // is_minted
+  1 +  // is_authorized
+  1;   // bump
+
+/// `NftStatusAccount` as first deployed, before accounts were versioned.
+#[derive(AnchorDeserialize)]
+struct LegacyNftStatus {
+  authority: Pubkey,
+  bump: u8,
+  rns_id: String,
+  merkle_root: String,
+  mint: Pubkey,
+}
+
+const LEGACY_NFT_STATUS_SIZE: usize = 8 +
+  400 + // authority, bump, rns_id and merkle_root
+  32;   // mint
+
+/// `RnsIdStatusAccount` and `ProjectAccount` as first deployed are the current
+/// layouts without the version byte and the fields appended since.
+const LEGACY_RNS_ID_STATUS_SIZE: usize = 8 +
+  32 + // authority
+  8;   // num
+pub(crate) const LEGACY_PROJECT_SIZE: usize = 3682;
+
+#[derive(Accounts)]
+#[instruction(kind: AccountKind, wallet: Pubkey)]
+pub struct MigrateAccountContext<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+
+  /// CHECK: May itself be on an old layout, read by `load_project`
+  #[account(
+    mut,
+    owner = crate::ID,
+    seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_ref()],
+    bump
+  )]
+  pub non_transferable_project: UncheckedAccount<'info>,
+
+  /// CHECK: Still on an old layout, checked by owner and discriminator
+  #[account(mut, owner = crate::ID)]
+  pub account: UncheckedAccount<'info>,
+
+  /// CHECK: Receives the rent freed by a smaller layout, checked in the handler
+  #[account(mut)]
+  pub rent_receiver: UncheckedAccount<'info>,
+
+  /// Needed to index the DIDs issued before the wallet index existed.
+  #[account(
+    init_if_needed,
+    payer = authority,
+    space = NON_TRANSFERABLE_WALLET_INDEX_SIZE,
+    seeds = [
+      NON_TRANSFERABLE_WALLET_INDEX_PREFIX.as_ref(),
+      wallet.key().as_ref()
+    ],
+    bump
+  )]
+  pub non_transferable_wallet_index: Option<Box<Account<'info, WalletIndexAccount>>>,
+
+  pub system_program: Program<'info, System>,
+}
+
+/// Accounts written before versioning are told apart by their size, which no
+/// versioned layout shares. Versioned ones are told apart by their version byte.
+fn check_legacy(data: &[u8], legacy_size: usize) -> Result<()> {
+  if data.len() == legacy_size {
+    return Ok(());
+  }
+  require!(data.len() <= 8 || data[8] != ACCOUNT_VERSION, ErrorCode::AlreadyMigrated);
+  err!(ErrorCode::InvalidLegacyAccount)
+}
+
+fn read_legacy<T: AnchorDeserialize>(data: &[u8], discriminator: [u8; 8]) -> Result<T> {
+  require!(data[..8] == discriminator, ErrorCode::InvalidLegacyAccount);
+  T::deserialize(&mut &data[8..]).map_err(|_| error!(ErrorCode::InvalidLegacyAccount))
+}
+
+/// Reads an account written before versioning: the current layout without the
+/// version byte and without the trailing fields added since.
+pub(crate) fn read_unversioned<T: AccountDeserialize>(data: &[u8], space: usize) -> Result<T> {
+  require!(data.len() >= 8 && data.len() < space, ErrorCode::InvalidLegacyAccount);
+  let mut buf = data[..8].to_vec();
+  buf.push(0);
+  buf.extend_from_slice(&data[8..]);
+  buf.resize(space, 0);
+  T::try_deserialize(&mut &buf[..])
+}
+
+/// Reads the project whatever its layout, since it may not be migrated yet.
+fn load_project(info: &AccountInfo) -> Result<ProjectAccount> {
+  let data = info.try_borrow_data()?;
+  if data.len() == LEGACY_PROJECT_SIZE {
+    read_unversioned(&data, NON_TRANSFERABLE_PROJECT_SIZE)
+  } else {
+    ProjectAccount::try_deserialize(&mut &data[..])
+  }
+}
+
+/// DIDs issued before payers were recorded were paid by the project authority.
+fn check_rent_receiver(accounts: &MigrateAccountContext, project: &ProjectAccount, payer: Pubkey) -> Result<()> {
+  let expected = if payer == Pubkey::default() {
+    project.authority
+  } else {
+    payer
+  };
+  require_keys_eq!(accounts.rent_receiver.key(), expected, ErrorCode::InvalidRentReceiver);
+  Ok(())
+}
+
+/// Resizes `account` to `space`, writes `value` and settles the rent difference.
+fn rewrite<T: AccountSerialize>(
+  accounts: &MigrateAccountContext,
+  value: &T,
+  space: usize,
+) -> Result<()> {
+  let account = accounts.account.to_account_info();
+  let rent = Rent::get()?.minimum_balance(space);
+
+  if account.lamports() < rent {
+    invoke(
+      &system_instruction::transfer(
+        accounts.authority.key,
+        account.key,
+        rent - account.lamports(),
+      ),
+      &[
+        accounts.authority.to_account_info(),
+        account.clone(),
+        accounts.system_program.to_account_info(),
+      ],
+    )?;
+  } else {
+    let excess = account.lamports() - rent;
+    **account.try_borrow_mut_lamports()? -= excess;
+    **accounts.rent_receiver.try_borrow_mut_lamports()? += excess;
+  }
+
+  account.realloc(space, true)?;
+  let mut data = account.try_borrow_mut_data()?;
+  data.fill(0);
+  let mut writer: &mut [u8] = &mut data;
+  value.try_serialize(&mut writer)
+}
+
+fn migrate_user_status(data: &[u8]) -> Result<UserStatusAccount> {
+  let legacy: LegacyUserStatus = read_legacy(data, UserStatusAccount::discriminator())?;
+  Ok(UserStatusAccount {
+    version: ACCOUNT_VERSION,
+    authority: legacy.authority,
+    rns_id_hash: rns_id_hash(&legacy.rns_id),
+    is_minted: legacy.is_minted,
+    is_authorized: legacy.is_authorized,
+    bump: legacy.bump,
+    ..Default::default()
+  })
+}
+
+fn migrate_nft_status(data: &[u8]) -> Result<NftStatusAccount> {
+  let legacy: LegacyNftStatus = read_legacy(data, NftStatusAccount::discriminator())?;
+  Ok(NftStatusAccount {
+    version: ACCOUNT_VERSION,
+    authority: legacy.authority,
+    bump: legacy.bump,
+    rns_id_hash: rns_id_hash(&legacy.rns_id),
+    // too long to keep for display, the hash still identifies it. Metadata
+    // can't be rebuilt without it, see `ProjectAccount::metadata_uri`.
+    rns_id: if legacy.rns_id.len() <= MAX_RNS_ID_LEN { legacy.rns_id } else { String::new() },
+    merkle_root: parse_merkle_root(&legacy.merkle_root)?,
+    mint: legacy.mint,
+    // not recorded before the token counter, `{index}` name templates show 0
+    ..Default::default()
+  })
+}
+
+pub fn handler(ctx: Context<MigrateAccountContext>, kind: AccountKind, wallet: Pubkey) -> Result<()> {
+  let project = load_project(&ctx.accounts.non_transferable_project)?;
+  require_keys_eq!(ctx.accounts.authority.key(), project.authority, ErrorCode::InvalidAuthority);
+
+  let data = ctx.accounts.account.try_borrow_data()?.to_vec();
+
+  match kind {
+    AccountKind::Project => {
+      require_keys_eq!(
+        ctx.accounts.account.key(),
+        ctx.accounts.non_transferable_project.key(),
+        ErrorCode::InvalidLegacyAccount
+      );
+      check_legacy(&data, LEGACY_PROJECT_SIZE)?;
+      check_rent_receiver(ctx.accounts, &project, Pubkey::default())?;
+
+      let mut project = project;
+      project.version = ACCOUNT_VERSION;
+      rewrite(ctx.accounts, &project, NON_TRANSFERABLE_PROJECT_SIZE)?;
+    }
+    AccountKind::UserStatus => {
+      check_legacy(&data, LEGACY_USER_STATUS_SIZE)?;
+      let mut status = migrate_user_status(&data)?;
+      require_keys_eq!(status.authority, wallet, ErrorCode::InvalidAuthority);
+      check_rent_receiver(ctx.accounts, &project, status.payer)?;
+
+      status.version = ACCOUNT_VERSION;
+      rewrite(ctx.accounts, &status, NON_TRANSFERABLE_USER_STATUS_SIZE)?;
+    }
+    AccountKind::NftStatus => {
+      check_legacy(&data, LEGACY_NFT_STATUS_SIZE)?;
+      let mut status = migrate_nft_status(&data)?;
+      require_keys_eq!(status.authority, wallet, ErrorCode::InvalidAuthority);
+      check_rent_receiver(ctx.accounts, &project, status.payer)?;
+
+      status.version = ACCOUNT_VERSION;
+      rewrite(ctx.accounts, &status, NON_TRANSFERABLE_NFT_STATUS_SIZE)?;
+
+      let Some(wallet_index) = ctx.accounts.non_transferable_wallet_index.as_mut() else {
+        return err!(ErrorCode::MissingWalletIndex);
+      };
+      wallet_index.version = ACCOUNT_VERSION;
+      wallet_index.wallet = wallet;
+      wallet_index.bump = *ctx.bumps.get("non_transferable_wallet_index").unwrap();
+      wallet_index.upsert(status.rns_id_hash, status.mint, status.did_status())?;
+    }
+    AccountKind::RnsIdStatus => {
+      check_legacy(&data, LEGACY_RNS_ID_STATUS_SIZE)?;
+      let mut status: RnsIdStatusAccount =
+        read_unversioned(&data, NON_TRANSFERABLE_RNS_ID_STATUS_SIZE)?;
+      check_rent_receiver(ctx.accounts, &project, Pubkey::default())?;
+
+      status.version = ACCOUNT_VERSION;
+      rewrite(ctx.accounts, &status, NON_TRANSFERABLE_RNS_ID_STATUS_SIZE)?;
+    }
+  }
+
+  emit!(MigrateAccountEvent {
+    account: ctx.accounts.account.key(),
+    kind,
+    wallet,
+  });
+
+  msg!("RNSMigrateAccount:_account:{};_wallet:{}", ctx.accounts.account.key(), wallet);
+
+  Ok(())
+}
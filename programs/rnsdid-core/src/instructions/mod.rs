@@ -1,17 +1,69 @@
-
 pub mod initialize;
 pub mod airdrop;
 pub mod verify;
+pub mod reissue;
+pub mod prove_rns_id;
+pub mod set_nft_attributes;
+pub mod refresh_metadata;
+pub mod update_collection_metadata;
+pub mod set_collection_size;
+pub mod create_rule_set;
 
 pub mod burn;
+pub mod revoke;
+pub mod set_mint_price;
+pub mod schedule_mint_price;
+pub mod set_jurisdiction_denied;
+pub mod set_sanctions_root;
+pub mod set_allowlist_mode;
+pub mod add_allowlist_entry;
+pub mod remove_allowlist_entry;
+pub mod set_allowlist_root;
 pub mod authorize_mint;
+pub mod cancel_authorization;
+
+pub mod create_campaign;
+pub mod update_campaign;
+pub mod create_tier;
+pub mod update_tier;
+pub mod upgrade_tier;
+pub mod claim_referral_rewards;
+
+pub mod migrate_account;
+pub mod import_v1_project;
+pub mod exchange_v1_did;
 
 pub use initialize::*;
 pub use airdrop::*;
 pub use verify::*;
+pub use reissue::*;
+pub use prove_rns_id::*;
+pub use set_nft_attributes::*;
+pub use refresh_metadata::*;
+pub use update_collection_metadata::*;
+pub use set_collection_size::*;
+pub use create_rule_set::*;
 
 pub use burn::*;
+pub use revoke::*;
+pub use set_mint_price::*;
+pub use schedule_mint_price::*;
+pub use set_jurisdiction_denied::*;
+pub use set_sanctions_root::*;
+pub use set_allowlist_mode::*;
+pub use add_allowlist_entry::*;
+pub use remove_allowlist_entry::*;
+pub use set_allowlist_root::*;
 pub use authorize_mint::*;
+pub use cancel_authorization::*;
 
+pub use create_campaign::*;
+pub use update_campaign::*;
+pub use create_tier::*;
+pub use update_tier::*;
+pub use upgrade_tier::*;
+pub use claim_referral_rewards::*;
 
-
+pub use migrate_account::*;
+pub use import_v1_project::*;
+pub use exchange_v1_did::*;
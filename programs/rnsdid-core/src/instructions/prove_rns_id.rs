@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+use crate::state::*;
+
+/// Checks that a DID issued in privacy mode commits to `rns_id` and `salt`.
+/// Meant to be simulated by a verifier: sending it would publish the rns_id.
+#[derive(Accounts)]
+pub struct ProveRnsIdContext<'info> {
+  pub non_transferable_nft_mint: Box<Account<'info, Mint>>,
+
+  #[account(
+    seeds = [
+      NON_TRANSFERABLE_NFT_STATUS_PREFIX.as_ref(),
+      non_transferable_nft_mint.key().as_ref()
+    ],
+    bump = non_transferable_nft_status.bump
+  )]
+  pub non_transferable_nft_status: Box<Account<'info, NftStatusAccount>>,
+}
+
+pub fn handler(ctx: Context<ProveRnsIdContext>, rns_id: String, salt: String) -> Result<()> {
+  let nft_status = &ctx.accounts.non_transferable_nft_status;
+  require!(nft_status.is_active(), ErrorCode::DidNotActive);
+  require!(
+    nft_status.matches_rns_id(&rns_id_commitment(&rns_id, &salt)),
+    ErrorCode::InvalidRnsIdProof
+  );
+
+  msg!(
+    "RNSProveID:_wallet:{};_tokenId:{}",
+    nft_status.authority,
+    nft_status.mint
+  );
+
+  Ok(())
+}
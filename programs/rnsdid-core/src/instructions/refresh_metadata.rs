@@ -0,0 +1,123 @@
+use anchor_lang::{prelude::*, solana_program::sysvar};
+use mpl_bubblegum::state::metaplex_anchor::MplTokenMetadata;
+use mpl_token_metadata::state::{DataV2, Metadata, TokenMetadataAccount, TokenStandard};
+
+use crate::error::ErrorCode;
+use crate::state::*;
+use crate::utils::{update_metadata_accounts_v2, update_v1, UpdateMetadataAccountsV2, UpdateV1};
+
+#[event]
+pub struct RefreshMetadataEvent {
+  pub mint: Pubkey,
+  pub uri: String,
+}
+
+/// Rewrites the name, symbol and URI of issued DIDs from the current project
+/// settings. `remaining_accounts` holds one `[nft_status, metadata, mint]` triple
+/// per DID, all of them in `tier`, or in the project collection when it is none.
+#[derive(Accounts)]
+pub struct RefreshMetadataContext<'info> {
+  /// Pays for the metadata of programmable DIDs if it grows.
+  #[account(mut)]
+  pub authority: Signer<'info>,
+
+  #[account(
+    constraint = non_transferable_project.authority == authority.key(),
+    seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_ref()],
+    bump = non_transferable_project.bump
+  )]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+  pub tier: Option<Box<Account<'info, TierAccount>>>,
+
+  /// CHECK: Only used for programmable DIDs
+  #[account(address = sysvar::instructions::ID)]
+  pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+  pub token_metadata_program: Program<'info, MplTokenMetadata>,
+  pub system_program: Program<'info, System>,
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, RefreshMetadataContext<'info>>) -> Result<()> {
+  let triples = ctx.remaining_accounts.chunks_exact(3);
+  require!(
+    !ctx.remaining_accounts.is_empty() && triples.remainder().is_empty(),
+    ErrorCode::InvalidDataProvided
+  );
+
+  let project = &ctx.accounts.non_transferable_project;
+  let tier = ctx.accounts.tier.as_deref().map(|tier| &**tier);
+  let project_signer_seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &[project.bump]];
+
+  for triple in triples {
+    let nft_status: Account<NftStatusAccount> = Account::try_from(&triple[0])?;
+    let status_key = Pubkey::create_program_address(
+      &[
+        NON_TRANSFERABLE_NFT_STATUS_PREFIX.as_bytes(),
+        nft_status.mint.as_ref(),
+        &[nft_status.bump],
+      ],
+      ctx.program_id,
+    )
+    .map_err(|_| ErrorCode::InvalidDataProvided)?;
+    require_keys_eq!(status_key, nft_status.key(), ErrorCode::InvalidDataProvided);
+    require!(nft_status.tier == tier.map_or(0, |tier| tier.tier), ErrorCode::TierMismatch);
+
+    // the metadata of a burned DID is closed
+    if nft_status.is_burned {
+      continue;
+    }
+
+    let metadata = Metadata::from_account_info(&triple[1])?;
+    require_keys_eq!(metadata.mint, nft_status.mint, ErrorCode::InvalidDataProvided);
+    require_keys_eq!(triple[2].key(), nft_status.mint, ErrorCode::InvalidDataProvided);
+
+    let uri = project.metadata_uri(tier, &nft_status.rns_id, &nft_status.mint, &nft_status.key())?;
+    let data = DataV2 {
+      name: project.metadata_name(&nft_status.rns_id, nft_status.token_index)?,
+      symbol: project.symbol.clone(),
+      uri: uri.clone(),
+      seller_fee_basis_points: metadata.data.seller_fee_basis_points,
+      creators: metadata.data.creators,
+      collection: metadata.collection,
+      uses: metadata.uses,
+    };
+
+    let cpi_program = ctx.accounts.token_metadata_program.to_account_info();
+    // token metadata rejects the legacy update on programmable NFTs
+    if metadata.token_standard == Some(TokenStandard::ProgrammableNonFungible) {
+      let Some(sysvar_instructions) = ctx.accounts.sysvar_instructions.as_ref() else {
+        return err!(ErrorCode::MissingProgrammableAccounts);
+      };
+      let cpi_accounts = UpdateV1 {
+        authority: project.to_account_info(),
+        mint: triple[2].clone(),
+        metadata: triple[1].clone(),
+        payer: ctx.accounts.authority.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        sysvar_instructions: sysvar_instructions.to_account_info(),
+      };
+      update_v1(
+        CpiContext::new(cpi_program, cpi_accounts).with_signer(&[&project_signer_seeds[..]]),
+        data,
+        None,
+      )?;
+    } else {
+      let cpi_accounts = UpdateMetadataAccountsV2 {
+        metadata: triple[1].clone(),
+        update_authority: project.to_account_info(),
+      };
+      update_metadata_accounts_v2(
+        CpiContext::new(cpi_program, cpi_accounts).with_signer(&[&project_signer_seeds[..]]),
+        data,
+      )?;
+    }
+
+    emit!(RefreshMetadataEvent {
+      mint: nft_status.mint,
+      uri,
+    });
+  }
+
+  Ok(())
+}
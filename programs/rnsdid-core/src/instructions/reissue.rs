@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+use crate::state::*;
+
+#[event]
+pub struct ReissueEvent {
+  pub rns_id: String,
+  pub wallet: Pubkey,
+  pub previous_mint: Pubkey,
+  pub mint: Pubkey,
+}
+
+/// Links a DID issued by `airdrop` + `verify` to the burned DID it replaces.
+/// Meant to run in the same transaction, right after `verify`.
+#[derive(Accounts)]
+#[instruction(rns_id: String, wallet: Pubkey)]
+pub struct ReissueContext<'info> {
+  pub authority: Signer<'info>,
+
+  #[account(
+    constraint = non_transferable_project.authority == authority.key(),
+    seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_ref()],
+    bump = non_transferable_project.bump
+  )]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+  #[account(
+    mut,
+    constraint = previous_nft_status.key() != non_transferable_nft_status.key() @ ErrorCode::InvalidReissue,
+  )]
+  pub previous_nft_status: Box<Account<'info, NftStatusAccount>>,
+
+  pub non_transferable_nft_mint: Box<Account<'info, Mint>>,
+
+  #[account(
+    mut,
+    seeds = [
+      NON_TRANSFERABLE_NFT_STATUS_PREFIX.as_ref(),
+      non_transferable_nft_mint.key().as_ref()
+    ],
+    bump = non_transferable_nft_status.bump
+  )]
+  pub non_transferable_nft_status: Box<Account<'info, NftStatusAccount>>,
+}
+
+pub fn handler(ctx: Context<ReissueContext>, rns_id: String, wallet: Pubkey) -> Result<()> {
+  let previous = &mut ctx.accounts.previous_nft_status;
+  let nft_status = &mut ctx.accounts.non_transferable_nft_status;
+
+  require!(
+    previous.is_burned
+      && previous.reissued_to == Pubkey::default()
+      && previous.authority == wallet
+      && previous.matches_rns_id(&rns_id),
+    ErrorCode::InvalidReissue
+  );
+  require!(
+    !nft_status.is_burned
+      && nft_status.previous_mint == Pubkey::default()
+      && nft_status.authority == wallet
+      && nft_status.matches_rns_id(&rns_id),
+    ErrorCode::InvalidReissue
+  );
+
+  previous.reissued_to = nft_status.mint;
+  nft_status.previous_mint = previous.mint;
+
+  emit!(ReissueEvent {
+    rns_id: rns_id.clone(),
+    wallet,
+    previous_mint: previous.mint,
+    mint: nft_status.mint,
+  });
+
+  msg!(
+    "RNSReissueID:_rnsId:{};_wallet:{};_previousTokenId:{};_tokenId:{}",
+    rns_id,
+    wallet,
+    previous.mint,
+    nft_status.mint
+  );
+
+  Ok(())
+}
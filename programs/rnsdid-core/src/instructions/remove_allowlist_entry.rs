@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::instructions::add_allowlist_entry::AllowlistEntryEvent;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(subject: [u8; 32])]
+pub struct RemoveAllowlistEntryContext<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+
+  #[account(
+    has_one = authority,
+    seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_ref()],
+    bump = non_transferable_project.bump
+  )]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+  #[account(
+    mut,
+    close = authority,
+    seeds = [NON_TRANSFERABLE_ALLOWLIST_PREFIX.as_ref(), subject.as_ref()],
+    bump = allowlist_entry.bump
+  )]
+  pub allowlist_entry: Box<Account<'info, AllowlistEntryAccount>>,
+}
+
+pub fn handler(_ctx: Context<RemoveAllowlistEntryContext>, subject: [u8; 32]) -> Result<()> {
+  emit!(AllowlistEntryEvent {
+    subject,
+    is_allowed: false,
+  });
+
+  Ok(())
+}
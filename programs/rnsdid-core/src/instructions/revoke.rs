@@ -0,0 +1,185 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::token::Mint;
+use mpl_bubblegum::state::metaplex_anchor::MplTokenMetadata;
+
+use crate::error::ErrorCode;
+use crate::state::*;
+use crate::utils::{unverify_collection_v1, UnverifyCollectionV1};
+
+#[event]
+pub struct RevokeEvent {
+  pub rns_id: String,
+  pub wallet: Pubkey,
+  pub token_id: String,
+}
+
+/// Lets the project authority invalidate a DID. The token stays frozen in the
+/// holder's wallet, but its status no longer counts as a valid Legal DID.
+#[derive(Accounts)]
+#[instruction(rns_id: String, wallet: Pubkey)]
+pub struct RevokeContext<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+
+  #[account(
+    constraint = non_transferable_project.authority == authority.key(),
+    seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_ref()],
+    bump = non_transferable_project.bump
+  )]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+  /// The tier the DID is issued in, none for the project collection.
+  pub tier: Option<Box<Account<'info, TierAccount>>>,
+
+  /// CHECK: The collection of `tier`, checked in the handler
+  pub non_transferable_project_mint: UncheckedAccount<'info>,
+
+  /// CHECK: Used in CPI So no Harm
+  #[account(mut)]
+  pub non_transferable_project_metadata: AccountInfo<'info>,
+
+  pub non_transferable_nft_mint: Box<Account<'info, Mint>>,
+
+  /// CHECK: Used in CPI
+  #[account(
+    mut,
+    seeds = [
+      "metadata".as_ref(),
+      token_metadata_program.key().as_ref(),
+      non_transferable_nft_mint.key().as_ref()
+    ],
+    seeds::program = token_metadata_program.key(),
+    bump,
+  )]
+  pub non_transferable_nft_metadata: AccountInfo<'info>,
+
+  #[account(
+    mut,
+    constraint = non_transferable_nft_status.authority == wallet,
+    constraint = non_transferable_nft_status.matches_rns_id(&rns_id) @ ErrorCode::RnsIsNotMatch,
+    seeds = [
+      NON_TRANSFERABLE_NFT_STATUS_PREFIX.as_ref(),
+      non_transferable_nft_mint.key().as_ref()
+    ],
+    bump = non_transferable_nft_status.bump
+  )]
+  pub non_transferable_nft_status: Box<Account<'info, NftStatusAccount>>,
+
+  #[account(
+    mut,
+    seeds = [
+      NON_TRANSFERABLE_NFT_USERSTATUS_PREFIX.as_ref(),
+      &hash_seed(&rns_id)[..32],
+      wallet.key().as_ref()
+    ],
+    bump = non_transferable_user_status.bump
+  )]
+  pub non_transferable_user_status: Box<Account<'info, UserStatusAccount>>,
+
+  #[account(
+    mut,
+    seeds = [
+      NON_TRANSFERABLE_NFT_RNSID_PREFIX.as_ref(),
+      &hash_seed(&rns_id)[..32],
+    ],
+    bump
+  )]
+  pub non_transferable_rns_id_status: Box<Account<'info, RnsIdStatusAccount>>,
+
+  #[account(
+    init_if_needed,
+    payer = authority,
+    space = NON_TRANSFERABLE_WALLET_INDEX_SIZE,
+    seeds = [
+      NON_TRANSFERABLE_WALLET_INDEX_PREFIX.as_ref(),
+      wallet.key().as_ref()
+    ],
+    bump
+  )]
+  pub non_transferable_wallet_index: Box<Account<'info, WalletIndexAccount>>,
+
+  /// Only DIDs that got an attributes record have one to keep in sync.
+  #[account(
+    mut,
+    seeds = [
+      NON_TRANSFERABLE_NFT_ATTRIBUTES_PREFIX.as_ref(),
+      non_transferable_nft_mint.key().as_ref()
+    ],
+    bump = non_transferable_nft_attributes.bump
+  )]
+  pub non_transferable_nft_attributes: Option<Box<Account<'info, NftAttributesAccount>>>,
+
+  /// CHECK: Read by token metadata
+  #[account(address = sysvar::instructions::ID)]
+  pub sysvar_instructions: UncheckedAccount<'info>,
+
+  pub token_metadata_program: Program<'info, MplTokenMetadata>,
+  pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<RevokeContext>, rns_id: String, wallet: Pubkey) -> Result<()> {
+  require!(ctx.accounts.non_transferable_nft_status.is_active(), ErrorCode::DidNotActive);
+
+  let project = &ctx.accounts.non_transferable_project;
+  let tier = ctx.accounts.tier.as_deref().map(|tier| &**tier);
+  require!(
+    tier.map_or(0, |tier| tier.tier) == ctx.accounts.non_transferable_nft_status.tier
+      && ctx.accounts.non_transferable_project_mint.key() == project.collection_mint(tier)?,
+    ErrorCode::TierMismatch
+  );
+
+  // Like a burn, a revoked DID no longer counts in its sized collection.
+  let project_signer_seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &[project.bump]];
+  let cpi_accounts = UnverifyCollectionV1 {
+    authority: project.to_account_info(),
+    metadata: ctx.accounts.non_transferable_nft_metadata.to_account_info(),
+    collection_mint: ctx.accounts.non_transferable_project_mint.to_account_info(),
+    collection_metadata: ctx.accounts.non_transferable_project_metadata.to_account_info(),
+    system_program: ctx.accounts.system_program.to_account_info(),
+    sysvar_instructions: ctx.accounts.sysvar_instructions.to_account_info(),
+  };
+  unverify_collection_v1(
+    CpiContext::new(ctx.accounts.token_metadata_program.to_account_info(), cpi_accounts)
+      .with_signer(&[&project_signer_seeds[..]]),
+  )?;
+
+  let nft_status = &mut ctx.accounts.non_transferable_nft_status;
+  nft_status.is_revoked = true;
+
+  let user_status = &mut ctx.accounts.non_transferable_user_status;
+  user_status.is_minted = false;
+  user_status.is_authorized = false;
+  // verify paid the authorization out, it can't be credited to a new one
+  user_status.paid_amount = 0;
+  user_status.referral_reward = 0;
+  user_status.referrer = Pubkey::default();
+
+  let rns_id_status = &mut ctx.accounts.non_transferable_rns_id_status;
+  rns_id_status.num = rns_id_status.num.saturating_sub(1);
+
+  if let Some(attributes) = ctx.accounts.non_transferable_nft_attributes.as_mut() {
+    attributes.status = DidStatus::Revoked;
+  }
+
+  let wallet_index = &mut ctx.accounts.non_transferable_wallet_index;
+  wallet_index.version = ACCOUNT_VERSION;
+  wallet_index.wallet = wallet;
+  wallet_index.bump = *ctx.bumps.get("non_transferable_wallet_index").unwrap();
+  wallet_index.upsert(rns_id_hash(&rns_id), nft_status.mint, DidStatus::Revoked)?;
+
+  msg!(
+    "RNSRevokeID:_rnsId:{};_wallet:{};_tokenId:{}",
+    rns_id,
+    wallet,
+    nft_status.mint
+  );
+
+  emit!(RevokeEvent {
+    rns_id: rns_id.clone(),
+    wallet,
+    token_id: nft_status.mint.to_string()
+  });
+
+  Ok(())
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::state::*;
+
+#[event]
+pub struct MintPriceScheduledEvent {
+  pub current_price: u64,
+  pub pending_price: u64,
+  pub effective_at: i64,
+}
+
+#[derive(Accounts)]
+pub struct ScheduleMintPriceContext<'info> {
+  pub authority: Signer<'info>,
+  #[account(mut, has_one = authority)]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+}
+
+pub fn handler(ctx: Context<ScheduleMintPriceContext>, mint_price: u64, effective_at: i64) -> Result<()> {
+  let now = Clock::get()?.unix_timestamp;
+  require!(effective_at > now, ErrorCode::InvalidEffectiveTime);
+
+  let non_transferable_project = &mut ctx.accounts.non_transferable_project;
+
+  // A previous schedule that is already due takes effect before being replaced.
+  let current_price = non_transferable_project.apply_pending_mint_price(now);
+
+  non_transferable_project.pending_mint_price = mint_price;
+  non_transferable_project.pending_price_effective_at = effective_at;
+
+  emit!(MintPriceScheduledEvent {
+    current_price,
+    pending_price: mint_price,
+    effective_at,
+  });
+
+  msg!(
+    "ScheduleMintPrice:collectionId:{}, price:{}, effectiveAt:{}",
+    non_transferable_project.to_account_info().key,
+    mint_price,
+    effective_at
+  );
+
+  Ok(())
+}
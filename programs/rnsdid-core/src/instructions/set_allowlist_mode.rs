@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+#[event]
+pub struct AllowlistModeEvent {
+  pub is_enabled: bool,
+}
+
+#[derive(Accounts)]
+pub struct SetAllowlistModeContext<'info> {
+  pub authority: Signer<'info>,
+  #[account(mut, has_one = authority)]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+}
+
+pub fn handler(ctx: Context<SetAllowlistModeContext>, is_enabled: bool) -> Result<()> {
+  ctx.accounts.non_transferable_project.allowlist_mode = is_enabled;
+
+  emit!(AllowlistModeEvent { is_enabled });
+
+  Ok(())
+}
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+#[event]
+pub struct AllowlistRootEvent {
+  pub allowlist_root: [u8; 32],
+  /// Number of wallets and rns_ids under the root, as reported by the caller.
+  pub entries: u64,
+}
+
+#[derive(Accounts)]
+pub struct SetAllowlistRootContext<'info> {
+  pub authority: Signer<'info>,
+  #[account(mut, has_one = authority)]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+}
+
+pub fn handler(ctx: Context<SetAllowlistRootContext>, allowlist_root: [u8; 32], entries: u64) -> Result<()> {
+  ctx.accounts.non_transferable_project.allowlist_root = allowlist_root;
+
+  emit!(AllowlistRootEvent {
+    allowlist_root,
+    entries,
+  });
+
+  Ok(())
+}
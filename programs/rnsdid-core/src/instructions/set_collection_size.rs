@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use mpl_bubblegum::state::metaplex_anchor::MplTokenMetadata;
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
+
+use crate::error::ErrorCode;
+use crate::state::*;
+use crate::utils::{set_collection_size, SetCollectionSize};
+
+#[event]
+pub struct SetCollectionSizeEvent {
+  pub size: u64,
+}
+
+/// One-time migration of a collection created before collections were sized.
+/// `size` is the number of verified DIDs, the sum of the `RnsIdStatusAccount`
+/// totals. Issuance fails on an unsized collection until this has run.
+#[derive(Accounts)]
+pub struct SetCollectionSizeContext<'info> {
+  pub authority: Signer<'info>,
+
+  #[account(
+    has_one = authority,
+    seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_ref()],
+    bump = non_transferable_project.bump
+  )]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+  #[account(
+    seeds = [NON_TRANSFERABLE_PROJECT_MINT_PREFIX.as_ref()],
+    bump = non_transferable_project.mint_bump,
+  )]
+  pub non_transferable_project_mint: Box<Account<'info, Mint>>,
+
+  /// CHECK: Used in CPI So no Harm
+  #[account(
+    mut,
+    seeds = [
+      "metadata".as_ref(),
+      token_metadata_program.key().as_ref(),
+      non_transferable_project_mint.key().as_ref()
+    ],
+    seeds::program = token_metadata_program.key(),
+    bump,
+  )]
+  pub non_transferable_project_metadata: AccountInfo<'info>,
+
+  pub token_metadata_program: Program<'info, MplTokenMetadata>,
+}
+
+pub fn handler(ctx: Context<SetCollectionSizeContext>, size: u64) -> Result<()> {
+  let metadata = Metadata::from_account_info(&ctx.accounts.non_transferable_project_metadata)?;
+  require!(metadata.collection_details.is_none(), ErrorCode::CollectionAlreadySized);
+
+  let project = &ctx.accounts.non_transferable_project;
+  let project_signer_seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &[project.bump]];
+  let cpi_accounts = SetCollectionSize {
+    metadata: ctx.accounts.non_transferable_project_metadata.to_account_info(),
+    update_authority: project.to_account_info(),
+    mint: ctx.accounts.non_transferable_project_mint.to_account_info(),
+  };
+  set_collection_size(
+    CpiContext::new(ctx.accounts.token_metadata_program.to_account_info(), cpi_accounts)
+      .with_signer(&[&project_signer_seeds[..]]),
+    size,
+  )?;
+
+  emit!(SetCollectionSizeEvent { size });
+
+  Ok(())
+}
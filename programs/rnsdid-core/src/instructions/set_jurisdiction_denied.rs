@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+#[event]
+pub struct JurisdictionDenylistEvent {
+  pub jurisdiction: [u8; 2],
+  pub is_denied: bool,
+  pub denied_count: u32,
+}
+
+#[derive(Accounts)]
+pub struct SetJurisdictionDeniedContext<'info> {
+  pub authority: Signer<'info>,
+  #[account(mut, has_one = authority)]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+}
+
+pub fn handler(
+  ctx: Context<SetJurisdictionDeniedContext>,
+  jurisdiction: [u8; 2],
+  is_denied: bool,
+) -> Result<()> {
+  let non_transferable_project = &mut ctx.accounts.non_transferable_project;
+
+  non_transferable_project.set_jurisdiction_denied(jurisdiction, is_denied)?;
+
+  emit!(JurisdictionDenylistEvent {
+    jurisdiction,
+    is_denied,
+    denied_count: non_transferable_project.denied_jurisdictions.len() as u32,
+  });
+
+  Ok(())
+}
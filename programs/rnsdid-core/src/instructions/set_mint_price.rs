@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+#[event]
+pub struct MintPriceChangedEvent {
+  pub previous_price: u64,
+  pub mint_price: u64,
+}
+
+#[derive(Accounts)]
+pub struct SetMintPriceContext<'info> {
+  pub authority: Signer<'info>,
+  #[account(mut, has_one = authority)]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+}
+
+pub fn handler(ctx: Context<SetMintPriceContext>, mint_price: u64) -> Result<()> {
+  let non_transferable_project = &mut ctx.accounts.non_transferable_project;
+
+  let previous_price = non_transferable_project.mint_price;
+  non_transferable_project.mint_price = mint_price;
+  // An explicit price overrides whatever was scheduled.
+  non_transferable_project.pending_mint_price = 0;
+  non_transferable_project.pending_price_effective_at = 0;
+
+  emit!(MintPriceChangedEvent {
+    previous_price,
+    mint_price,
+  });
+
+  msg!(
+    "SetMintPrice:collectionId:{}, price:{}",
+    non_transferable_project.to_account_info().key,
+    non_transferable_project.mint_price.to_string()
+  );
+
+  Ok(())
+}
@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::error::ErrorCode;
+use crate::state::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct NftAttributesArgs {
+  pub kyc_level: u8,
+  pub issuer: Pubkey,
+  pub expires_at: i64,
+  pub jurisdiction: [u8; 2],
+}
+
+impl NftAttributesArgs {
+  pub fn apply(&self, attributes: &mut NftAttributesAccount) -> Result<()> {
+    require!(
+      self.jurisdiction == [0; 2] || is_jurisdiction_code(self.jurisdiction),
+      ErrorCode::InvalidJurisdiction
+    );
+
+    attributes.kyc_level = self.kyc_level;
+    attributes.issuer = self.issuer;
+    attributes.issued_at = Clock::get()?.unix_timestamp;
+    attributes.expires_at = self.expires_at;
+    attributes.jurisdiction = self.jurisdiction;
+    Ok(())
+  }
+}
+
+#[event]
+pub struct NftAttributesEvent {
+  pub mint: Pubkey,
+  pub kyc_level: u8,
+  pub issuer: Pubkey,
+  pub expires_at: i64,
+  pub jurisdiction: [u8; 2],
+}
+
+#[derive(Accounts)]
+pub struct SetNftAttributesContext<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+
+  #[account(
+    constraint = non_transferable_project.authority == authority.key(),
+    seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_ref()],
+    bump = non_transferable_project.bump
+  )]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+  pub non_transferable_nft_mint: Box<Account<'info, Mint>>,
+
+  #[account(
+    seeds = [
+      NON_TRANSFERABLE_NFT_STATUS_PREFIX.as_ref(),
+      non_transferable_nft_mint.key().as_ref()
+    ],
+    bump = non_transferable_nft_status.bump
+  )]
+  pub non_transferable_nft_status: Box<Account<'info, NftStatusAccount>>,
+
+  #[account(
+    init_if_needed,
+    payer = authority,
+    space = NON_TRANSFERABLE_NFT_ATTRIBUTES_SIZE,
+    seeds = [
+      NON_TRANSFERABLE_NFT_ATTRIBUTES_PREFIX.as_ref(),
+      non_transferable_nft_mint.key().as_ref()
+    ],
+    bump
+  )]
+  pub non_transferable_nft_attributes: Box<Account<'info, NftAttributesAccount>>,
+
+  pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SetNftAttributesContext>, args: NftAttributesArgs) -> Result<()> {
+  let attributes = &mut ctx.accounts.non_transferable_nft_attributes;
+
+  attributes.version = ACCOUNT_VERSION;
+  attributes.mint = ctx.accounts.non_transferable_nft_mint.key();
+  attributes.status = ctx.accounts.non_transferable_nft_status.did_status();
+  attributes.bump = *ctx.bumps.get("non_transferable_nft_attributes").unwrap();
+  args.apply(attributes)?;
+
+  emit!(NftAttributesEvent {
+    mint: attributes.mint,
+    kyc_level: attributes.kyc_level,
+    issuer: attributes.issuer,
+    expires_at: attributes.expires_at,
+    jurisdiction: attributes.jurisdiction,
+  });
+
+  Ok(())
+}
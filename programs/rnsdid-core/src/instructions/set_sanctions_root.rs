@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::state::*;
+
+#[event]
+pub struct SanctionsRootEvent {
+  pub sanctions_root: [u8; 32],
+  /// Number of sanctioned wallets under the root, as reported by the caller.
+  pub wallets: u64,
+  pub updated_by: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct SetSanctionsRootContext<'info> {
+  /// The project authority or its compliance authority.
+  pub authority: Signer<'info>,
+
+  #[account(
+    mut,
+    constraint = authority.key() == non_transferable_project.authority
+      || authority.key() == non_transferable_project.compliance_authority
+      @ ErrorCode::InvalidAuthority,
+    seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_ref()],
+    bump = non_transferable_project.bump
+  )]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+}
+
+pub fn handler(
+  ctx: Context<SetSanctionsRootContext>,
+  sanctions_root: [u8; 32],
+  wallets: u64,
+) -> Result<()> {
+  ctx.accounts.non_transferable_project.sanctions_root = sanctions_root;
+
+  emit!(SanctionsRootEvent {
+    sanctions_root,
+    wallets,
+    updated_by: ctx.accounts.authority.key(),
+  });
+
+  Ok(())
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::instructions::create_campaign::{CampaignArgs, CampaignEvent};
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(campaign_id: u64)]
+pub struct UpdateCampaignContext<'info> {
+  pub authority: Signer<'info>,
+
+  #[account(
+    has_one = authority,
+    seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_ref()],
+    bump = non_transferable_project.bump
+  )]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+  #[account(
+    mut,
+    seeds = [
+      NON_TRANSFERABLE_CAMPAIGN_PREFIX.as_ref(),
+      campaign_id.to_le_bytes().as_ref()
+    ],
+    bump = campaign.bump
+  )]
+  pub campaign: Box<Account<'info, CampaignAccount>>,
+}
+
+pub fn handler(ctx: Context<UpdateCampaignContext>, campaign_id: u64, args: CampaignArgs) -> Result<()> {
+  let campaign = &mut ctx.accounts.campaign;
+
+  args.apply(campaign)?;
+
+  emit!(CampaignEvent {
+    campaign_id,
+    discount_kind: campaign.discount_kind,
+    discount_value: campaign.discount_value,
+    max_uses: campaign.max_uses,
+    is_active: campaign.is_active,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use mpl_bubblegum::state::metaplex_anchor::MplTokenMetadata;
+use mpl_token_metadata::state::{Creator, DataV2, Metadata, TokenMetadataAccount};
+
+use crate::error::ErrorCode;
+use crate::state::*;
+use crate::utils::{update_metadata_accounts_v2, UpdateMetadataAccountsV2};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CollectionMetadataArgs {
+  pub name: String,
+  pub symbol: String,
+  pub uri: String,
+  /// Shares must add up to 100, token metadata checks them.
+  pub creators: Vec<CreatorShare>,
+  pub seller_fee_basis_points: u16,
+}
+
+#[event]
+pub struct UpdateCollectionMetadataEvent {
+  pub name: String,
+  pub symbol: String,
+  pub uri: String,
+  pub creators: Vec<CreatorShare>,
+  pub seller_fee_basis_points: u16,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCollectionMetadataContext<'info> {
+  pub authority: Signer<'info>,
+
+  #[account(
+    has_one = authority,
+    seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_ref()],
+    bump = non_transferable_project.bump
+  )]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+  #[account(
+    seeds = [NON_TRANSFERABLE_PROJECT_MINT_PREFIX.as_ref()],
+    bump = non_transferable_project.mint_bump,
+  )]
+  pub non_transferable_project_mint: Box<Account<'info, Mint>>,
+
+  /// CHECK: Checked against the collection mint in the handler
+  #[account(mut)]
+  pub non_transferable_project_metadata: AccountInfo<'info>,
+
+  pub token_metadata_program: Program<'info, MplTokenMetadata>,
+}
+
+pub fn handler(ctx: Context<UpdateCollectionMetadataContext>, args: CollectionMetadataArgs) -> Result<()> {
+  let project = &ctx.accounts.non_transferable_project;
+
+  let metadata = Metadata::from_account_info(&ctx.accounts.non_transferable_project_metadata)?;
+  require_keys_eq!(
+    metadata.mint,
+    ctx.accounts.non_transferable_project_mint.key(),
+    ErrorCode::InvalidDataProvided
+  );
+
+  // the project signs as update authority, so it may verify itself; other
+  // creators keep whatever they signed before
+  let previous = metadata.data.creators.unwrap_or_default();
+  let creators: Vec<Creator> = args
+    .creators
+    .iter()
+    .map(|creator| Creator {
+      address: creator.address,
+      verified: creator.address == project.key()
+        || previous.iter().any(|c| c.address == creator.address && c.verified),
+      share: creator.share,
+    })
+    .collect();
+
+  let data = DataV2 {
+    name: args.name.clone(),
+    symbol: args.symbol.clone(),
+    uri: args.uri.clone(),
+    seller_fee_basis_points: args.seller_fee_basis_points,
+    creators: if creators.is_empty() { None } else { Some(creators) },
+    collection: metadata.collection,
+    uses: metadata.uses,
+  };
+
+  let project_signer_seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &[project.bump]];
+  let cpi_accounts = UpdateMetadataAccountsV2 {
+    metadata: ctx.accounts.non_transferable_project_metadata.to_account_info(),
+    update_authority: project.to_account_info(),
+  };
+  update_metadata_accounts_v2(
+    CpiContext::new(ctx.accounts.token_metadata_program.to_account_info(), cpi_accounts)
+      .with_signer(&[&project_signer_seeds[..]]),
+    data,
+  )?;
+
+  emit!(UpdateCollectionMetadataEvent {
+    name: args.name,
+    symbol: args.symbol,
+    uri: args.uri,
+    creators: args.creators,
+    seller_fee_basis_points: args.seller_fee_basis_points,
+  });
+
+  Ok(())
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::instructions::create_tier::{TierArgs, TierEvent};
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(tier: u8)]
+pub struct UpdateTierContext<'info> {
+  pub authority: Signer<'info>,
+
+  #[account(
+    has_one = authority,
+    seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_ref()],
+    bump = non_transferable_project.bump
+  )]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+  #[account(
+    mut,
+    seeds = [NON_TRANSFERABLE_TIER_PREFIX.as_ref(), tier.to_le_bytes().as_ref()],
+    bump = tier_account.bump
+  )]
+  pub tier_account: Box<Account<'info, TierAccount>>,
+}
+
+pub fn handler(ctx: Context<UpdateTierContext>, tier: u8, args: TierArgs) -> Result<()> {
+  let tier_account = &mut ctx.accounts.tier_account;
+
+  args.apply(tier_account)?;
+
+  emit!(TierEvent {
+    tier,
+    mint: tier_account.mint,
+    mint_price: tier_account.mint_price,
+    max_supply: tier_account.max_supply,
+    is_active: tier_account.is_active,
+  });
+
+  Ok(())
+}
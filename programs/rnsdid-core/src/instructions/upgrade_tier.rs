@@ -0,0 +1,258 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use mpl_bubblegum::state::metaplex_anchor::MplTokenMetadata;
+use mpl_token_metadata::state::{DataV2, Metadata, TokenMetadataAccount, TokenStandard};
+
+use crate::error::ErrorCode;
+use crate::state::*;
+use crate::utils::{
+  set_and_verify_sized_collection_item, unverify_collection_v1, unverify_sized_collection_item,
+  update_metadata_accounts_v2, update_v1, verify_collection_v1, UnverifyCollectionV1,
+  UpdateMetadataAccountsV2, UpdateV1, VerifyCollection, VerifyCollectionV1,
+};
+
+#[event]
+pub struct UpgradeTierEvent {
+  pub mint: Pubkey,
+  pub from_tier: u8,
+  pub to_tier: u8,
+}
+
+/// Moves an issued DID from its tier, or the project collection, to `tier`.
+/// Tier 0 moves it back to the project collection.
+#[derive(Accounts)]
+#[instruction(tier: u8)]
+pub struct UpgradeTierContext<'info> {
+  #[account(mut)]
+  pub authority: Signer<'info>,
+
+  #[account(
+    has_one = authority,
+    seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_ref()],
+    bump = non_transferable_project.bump
+  )]
+  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+  #[account(
+    mut,
+    seeds = [
+      NON_TRANSFERABLE_NFT_STATUS_PREFIX.as_ref(),
+      non_transferable_nft_status.mint.as_ref()
+    ],
+    bump = non_transferable_nft_status.bump
+  )]
+  pub non_transferable_nft_status: Box<Account<'info, NftStatusAccount>>,
+
+  #[account(
+    mut,
+    seeds = [
+      NON_TRANSFERABLE_NFT_USERSTATUS_PREFIX.as_ref(),
+      non_transferable_nft_status.rns_id_hash.as_ref(),
+      non_transferable_nft_status.authority.as_ref()
+    ],
+    bump = non_transferable_user_status.bump
+  )]
+  pub non_transferable_user_status: Box<Account<'info, UserStatusAccount>>,
+
+  /// CHECK: Checked against the status mint
+  #[account(address = non_transferable_nft_status.mint)]
+  pub non_transferable_nft_mint: UncheckedAccount<'info>,
+
+  /// CHECK: Checked against the status mint in the handler
+  #[account(mut)]
+  pub non_transferable_nft_metadata: UncheckedAccount<'info>,
+
+  /// The current tier of the DID, none for the project collection.
+  pub current_tier: Option<Box<Account<'info, TierAccount>>>,
+
+  /// CHECK: The current collection, checked in the handler
+  pub current_collection_mint: UncheckedAccount<'info>,
+
+  /// CHECK: Used in CPI So no Harm
+  #[account(mut)]
+  pub current_collection_metadata: AccountInfo<'info>,
+
+  /// CHECK: Used in CPI So no Harm
+  pub current_collection_master_edition: AccountInfo<'info>,
+
+  /// The tier to move to, none for tier 0.
+  #[account(
+    mut,
+    seeds = [NON_TRANSFERABLE_TIER_PREFIX.as_ref(), tier.to_le_bytes().as_ref()],
+    bump = tier_account.bump
+  )]
+  pub tier_account: Option<Box<Account<'info, TierAccount>>>,
+
+  /// CHECK: The new collection, checked in the handler
+  pub tier_mint: UncheckedAccount<'info>,
+
+  /// CHECK: Used in CPI So no Harm
+  #[account(mut)]
+  pub tier_metadata: AccountInfo<'info>,
+
+  /// CHECK: Used in CPI So no Harm
+  pub tier_master_edition: AccountInfo<'info>,
+
+  /// CHECK: Only used for programmable DIDs
+  #[account(address = sysvar::instructions::ID)]
+  pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+  pub token_metadata_program: Program<'info, MplTokenMetadata>,
+  pub system_program: Program<'info, System>,
+}
+
+/// Moves a programmable DID with the V1 instructions, the legacy collection
+/// instructions reject programmable NFTs.
+fn move_programmable<'info>(
+  accounts: &UpgradeTierContext<'info>,
+  sysvar_instructions: &AccountInfo<'info>,
+  signer_seeds: &[&[u8]],
+  data: DataV2,
+) -> Result<()> {
+  let project = &accounts.non_transferable_project;
+  let cpi_program = accounts.token_metadata_program.to_account_info();
+
+  let cpi_accounts = UnverifyCollectionV1 {
+    authority: project.to_account_info(),
+    metadata: accounts.non_transferable_nft_metadata.to_account_info(),
+    collection_mint: accounts.current_collection_mint.to_account_info(),
+    collection_metadata: accounts.current_collection_metadata.to_account_info(),
+    system_program: accounts.system_program.to_account_info(),
+    sysvar_instructions: sysvar_instructions.clone(),
+  };
+  unverify_collection_v1(CpiContext::new(cpi_program.clone(), cpi_accounts).with_signer(&[signer_seeds]))?;
+
+  let cpi_accounts = UpdateV1 {
+    authority: project.to_account_info(),
+    mint: accounts.non_transferable_nft_mint.to_account_info(),
+    metadata: accounts.non_transferable_nft_metadata.to_account_info(),
+    payer: accounts.authority.to_account_info(),
+    system_program: accounts.system_program.to_account_info(),
+    sysvar_instructions: sysvar_instructions.clone(),
+  };
+  update_v1(
+    CpiContext::new(cpi_program.clone(), cpi_accounts).with_signer(&[signer_seeds]),
+    data,
+    Some(accounts.tier_mint.key()),
+  )?;
+
+  let cpi_accounts = VerifyCollectionV1 {
+    authority: project.to_account_info(),
+    metadata: accounts.non_transferable_nft_metadata.to_account_info(),
+    collection_mint: accounts.tier_mint.to_account_info(),
+    collection_metadata: accounts.tier_metadata.to_account_info(),
+    collection_master_edition: accounts.tier_master_edition.to_account_info(),
+    system_program: accounts.system_program.to_account_info(),
+    sysvar_instructions: sysvar_instructions.clone(),
+  };
+  verify_collection_v1(CpiContext::new(cpi_program, cpi_accounts).with_signer(&[signer_seeds]))
+}
+
+pub fn handler(ctx: Context<UpgradeTierContext>, tier: u8) -> Result<()> {
+  let project = &ctx.accounts.non_transferable_project;
+  let nft_status = &ctx.accounts.non_transferable_nft_status;
+  require!(nft_status.is_active(), ErrorCode::InvalidDataProvided);
+
+  let from_tier = nft_status.tier;
+  require!(from_tier != tier, ErrorCode::TierMismatch);
+  let current_tier = ctx.accounts.current_tier.as_deref().map(|tier| &**tier);
+  require!(
+    current_tier.map_or(0, |tier| tier.tier) == from_tier
+      && ctx.accounts.current_collection_mint.key() == project.collection_mint(current_tier)?,
+    ErrorCode::TierMismatch
+  );
+  let new_tier = ctx.accounts.tier_account.as_deref().map(|tier| &**tier);
+  require!(
+    new_tier.map_or(0, |tier| tier.tier) == tier
+      && ctx.accounts.tier_mint.key() == project.collection_mint(new_tier)?,
+    ErrorCode::TierMismatch
+  );
+
+  let metadata = Metadata::from_account_info(&ctx.accounts.non_transferable_nft_metadata)?;
+  require_keys_eq!(metadata.mint, nft_status.mint, ErrorCode::InvalidDataProvided);
+
+  let project_signer_seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &[project.bump]];
+  let accounts = &ctx.accounts;
+
+  // point the DID at the base URI of its new tier
+  let uri = project.metadata_uri(
+    new_tier,
+    &nft_status.rns_id,
+    &nft_status.mint,
+    &nft_status.key(),
+  )?;
+  let data = DataV2 {
+    name: metadata.data.name.trim_end_matches('\0').to_string(),
+    symbol: metadata.data.symbol.trim_end_matches('\0').to_string(),
+    uri,
+    seller_fee_basis_points: metadata.data.seller_fee_basis_points,
+    creators: metadata.data.creators,
+    collection: metadata.collection,
+    uses: metadata.uses,
+  };
+
+  if metadata.token_standard == Some(TokenStandard::ProgrammableNonFungible) {
+    let Some(sysvar_instructions) = accounts.sysvar_instructions.as_ref() else {
+      return err!(ErrorCode::MissingProgrammableAccounts);
+    };
+    move_programmable(accounts, &sysvar_instructions.to_account_info(), &project_signer_seeds, data)?;
+  } else {
+    let cpi_accounts = VerifyCollection {
+      payer: accounts.authority.to_account_info(),
+      metadata: accounts.non_transferable_nft_metadata.to_account_info(),
+      collection_authority: project.to_account_info(),
+      collection_mint: accounts.current_collection_mint.to_account_info(),
+      collection_metadata: accounts.current_collection_metadata.to_account_info(),
+      collection_master_edition: accounts.current_collection_master_edition.to_account_info(),
+    };
+    unverify_sized_collection_item(
+      CpiContext::new(accounts.token_metadata_program.to_account_info(), cpi_accounts)
+        .with_signer(&[&project_signer_seeds[..]]),
+      None,
+    )?;
+
+    let cpi_accounts = VerifyCollection {
+      payer: accounts.authority.to_account_info(),
+      metadata: accounts.non_transferable_nft_metadata.to_account_info(),
+      collection_authority: project.to_account_info(),
+      collection_mint: accounts.tier_mint.to_account_info(),
+      collection_metadata: accounts.tier_metadata.to_account_info(),
+      collection_master_edition: accounts.tier_master_edition.to_account_info(),
+    };
+    set_and_verify_sized_collection_item(
+      CpiContext::new(accounts.token_metadata_program.to_account_info(), cpi_accounts)
+        .with_signer(&[&project_signer_seeds[..]]),
+      None,
+    )?;
+
+    // the collection was just moved, the data update must not move it back
+    let data = DataV2 {
+      collection: Metadata::from_account_info(&accounts.non_transferable_nft_metadata)?.collection,
+      ..data
+    };
+    let cpi_accounts = UpdateMetadataAccountsV2 {
+      metadata: accounts.non_transferable_nft_metadata.to_account_info(),
+      update_authority: project.to_account_info(),
+    };
+    update_metadata_accounts_v2(
+      CpiContext::new(accounts.token_metadata_program.to_account_info(), cpi_accounts)
+        .with_signer(&[&project_signer_seeds[..]]),
+      data,
+    )?;
+  }
+
+  if let Some(tier_account) = ctx.accounts.tier_account.as_mut() {
+    tier_account.admit()?;
+  }
+  ctx.accounts.non_transferable_user_status.tier = tier;
+  let nft_status = &mut ctx.accounts.non_transferable_nft_status;
+  nft_status.tier = tier;
+
+  emit!(UpgradeTierEvent {
+    mint: nft_status.mint,
+    from_tier,
+    to_tier: tier,
+  });
+
+  Ok(())
+}
@@ -1,19 +1,21 @@
 use crate::utils::{
-  create_metadata_accounts_v3, verify_collection, CreateMetadataAccountsV3, VerifyCollection,
+  create_metadata_accounts_v3, create_v1, mint_v1, parse_merkle_root, sign_metadata,
+  verify_collection_v1, verify_creator_v1, verify_sized_collection_item, CreateMetadataAccountsV3,
+  CreateV1, MintV1, SignMetadata, VerifyCollection, VerifyCollectionV1, VerifyCreatorV1,
 };
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::sysvar::rent::Rent;
+use anchor_lang::solana_program::sysvar::{self, rent::Rent};
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{Mint, Token, TokenAccount};
 use mpl_bubblegum::state::metaplex_anchor::MplTokenMetadata;
 
-use mpl_token_metadata::state::{Collection, Creator, DataV2};
+use mpl_token_metadata::state::{AssetData, Collection, Creator, DataV2, TokenStandard};
 
 use crate::error::ErrorCode;
 use crate::state::*;
 
 #[derive(Accounts)]
-#[instruction(rns_id: String, wallet:Pubkey, merkle_root: String, index: String)]
+#[instruction(rns_id: String, wallet:Pubkey, merkle_root: String, index: String, sanctions_proof: Option<SanctionsProof>)]
 pub struct VerifyContext<'info> {
   #[account(mut)]
   pub authority: Signer<'info>,
@@ -26,11 +28,12 @@ pub struct VerifyContext<'info> {
   )]
   pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
 
-  #[account(
-    mut,
-    seeds = [NON_TRANSFERABLE_PROJECT_MINT_PREFIX.as_ref()],
-    bump = non_transferable_project.mint_bump,
-  )]
+  /// The tier the DID is issued in, none for the project collection.
+  #[account(mut)]
+  pub tier: Option<Box<Account<'info, TierAccount>>>,
+
+  /// The collection of `tier`, checked in the handler.
+  #[account(mut)]
   pub non_transferable_project_mint: Box<Account<'info, Mint>>,
 
   /// CHECK: Used in CPI So no Harm
@@ -41,17 +44,14 @@ pub struct VerifyContext<'info> {
   #[account(mut)]
   pub non_transferable_project_master_edition: AccountInfo<'info>,
 
+  /// Created and numbered by `airdrop` earlier in the same transaction.
   #[account(
-    init_if_needed,
-    payer = authority,
+    mut,
     seeds = [
       NON_TRANSFERABLE_NFT_MINT_PREFIX.as_ref(),
       index.as_ref()
     ],
     bump,
-    mint::decimals = 0,
-    mint::authority = non_transferable_project,
-    mint::freeze_authority = non_transferable_project
   )]
   pub non_transferable_nft_mint: Box<Account<'info, Mint>>,
 
@@ -70,12 +70,7 @@ pub struct VerifyContext<'info> {
   #[account(
       init_if_needed,
       payer = authority,
-      space = 8 +
-      32 +        // authority
-      50 +        // rns_id
-      1 +         // is_minted
-      1 +         // is_authorized
-      1,          // bump
+      space = NON_TRANSFERABLE_USER_STATUS_SIZE,
       seeds = [
           NON_TRANSFERABLE_NFT_USERSTATUS_PREFIX.as_ref(),
           &hash_seed(&rns_id)[..32],
@@ -88,9 +83,7 @@ pub struct VerifyContext<'info> {
   #[account(
     init_if_needed,
     payer = authority,
-    space = 8 +
-    8 +
-    32,
+    space = NON_TRANSFERABLE_RNS_ID_STATUS_SIZE,
     seeds = [
         NON_TRANSFERABLE_NFT_RNSID_PREFIX.as_ref(),
         &hash_seed(&rns_id)[..32],
@@ -100,19 +93,57 @@ pub struct VerifyContext<'info> {
   pub non_transferable_rns_id_status: Box<Account<'info, RnsIdStatusAccount>>,
 
   #[account(
-    init_if_needed,
-    payer = authority,
-    space =  8 +
-    400 +
-    32,
+    mut,
     seeds = [
         NON_TRANSFERABLE_NFT_STATUS_PREFIX.as_ref(),
         non_transferable_nft_mint.key().as_ref()
     ],
-    bump
+    bump = non_transferable_nft_status.bump
   )]
   pub non_transferable_nft_status: Box<Account<'info, NftStatusAccount>>,
 
+  #[account(
+    init_if_needed,
+    payer = authority,
+    space = NON_TRANSFERABLE_WALLET_INDEX_SIZE,
+    seeds = [
+        NON_TRANSFERABLE_WALLET_INDEX_PREFIX.as_ref(),
+        user_account.key().as_ref()
+    ],
+    bump
+  )]
+  pub non_transferable_wallet_index: Box<Account<'info, WalletIndexAccount>>,
+
+  /// CHECK: Receives the fee escrowed by `authorize_mint`
+  #[account(
+    mut,
+    address = non_transferable_project.fee_recipient @ ErrorCode::InvalidFeeRecipient
+  )]
+  pub fee_recipient: AccountInfo<'info>,
+
+  /// Credited with the referral reward escrowed by `authorize_mint`, needed
+  /// when the authorization named a referrer.
+  #[account(
+    mut,
+    seeds = [
+      NON_TRANSFERABLE_REFERRAL_PREFIX.as_ref(),
+      non_transferable_user_status.referrer.as_ref()
+    ],
+    bump = referral_stats.bump
+  )]
+  pub referral_stats: Option<Box<Account<'info, ReferralStatsAccount>>>,
+
+  /// Records the attested jurisdiction when the DID already has attributes.
+  #[account(
+    mut,
+    seeds = [
+      NON_TRANSFERABLE_NFT_ATTRIBUTES_PREFIX.as_ref(),
+      non_transferable_nft_mint.key().as_ref()
+    ],
+    bump = non_transferable_nft_attributes.bump
+  )]
+  pub non_transferable_nft_attributes: Option<Box<Account<'info, NftAttributesAccount>>>,
+
   /// CHECK: Used in CPI
   #[account(mut)]
   pub non_transferable_nft_metadata: UncheckedAccount<'info>,
@@ -120,6 +151,22 @@ pub struct VerifyContext<'info> {
   #[account(mut)]
   pub non_transferable_nft_master_edition: UncheckedAccount<'info>,
 
+  /// CHECK: Created by token metadata, only used for programmable DIDs
+  #[account(mut)]
+  pub non_transferable_nft_token_record: Option<UncheckedAccount<'info>>,
+
+  /// CHECK: Only used for programmable DIDs
+  #[account(address = sysvar::instructions::ID)]
+  pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+  /// CHECK: Only used for programmable DIDs
+  #[account(address = mpl_token_auth_rules::ID)]
+  pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+  /// CHECK: Only used for programmable DIDs
+  #[account(address = non_transferable_project.rule_set)]
+  pub authorization_rules: Option<UncheckedAccount<'info>>,
+
   pub token_metadata_program: Program<'info, MplTokenMetadata>,
   pub associated_token_program: Program<'info, AssociatedToken>,
   pub token_program: Program<'info, Token>,
@@ -161,13 +208,158 @@ impl<'info> VerifyContext<'info> {
   }
 }
 
+/// Pays out the fee `authorize_mint` escrowed on the user status, to the fee
+/// recipient and to the referrer, which only now counts the referral.
+fn release_payment(accounts: &mut VerifyContext) -> Result<()> {
+  let user_status = &mut accounts.non_transferable_user_status;
+  let (payment, reward) = (user_status.paid_amount, user_status.referral_reward);
+
+  **user_status.to_account_info().try_borrow_mut_lamports()? -= payment;
+  **accounts.fee_recipient.try_borrow_mut_lamports()? += payment;
+
+  if user_status.referrer != Pubkey::default() {
+    let Some(stats) = accounts.referral_stats.as_mut() else {
+      return err!(ErrorCode::InvalidReferrer);
+    };
+    **user_status.to_account_info().try_borrow_mut_lamports()? -= reward;
+    **stats.to_account_info().try_borrow_mut_lamports()? += reward;
+    stats.referrals += 1;
+    stats.pending_rewards += reward;
+    stats.total_rewards += reward;
+  }
+
+  user_status.paid_amount = 0;
+  user_status.referral_reward = 0;
+  user_status.referrer = Pubkey::default();
+  Ok(())
+}
+
+/// Issues the DID as a programmable NFT under the project RuleSet: creates its
+/// metadata and master edition, mints it and verifies it in the collection.
+fn issue_programmable(
+  ctx: &Context<VerifyContext>,
+  data: DataV2,
+  sign_as_creator: bool,
+  project_signer_seeds: &[&[u8]],
+) -> Result<()> {
+  let accounts = &ctx.accounts;
+  let (
+    Some(token_record),
+    Some(sysvar_instructions),
+    Some(authorization_rules_program),
+    Some(authorization_rules),
+  ) = (
+    accounts.non_transferable_nft_token_record.as_ref(),
+    accounts.sysvar_instructions.as_ref(),
+    accounts.authorization_rules_program.as_ref(),
+    accounts.authorization_rules.as_ref(),
+  ) else {
+    return err!(ErrorCode::MissingProgrammableAccounts);
+  };
+
+  let mut asset_data = AssetData::new(
+    TokenStandard::ProgrammableNonFungible,
+    data.name,
+    data.symbol,
+    data.uri,
+  );
+  asset_data.creators = data.creators;
+  asset_data.collection = data.collection;
+  asset_data.is_mutable = true;
+  asset_data.rule_set = Some(accounts.non_transferable_project.rule_set);
+
+  let cpi_accounts = CreateV1 {
+    metadata: accounts.non_transferable_nft_metadata.to_account_info(),
+    master_edition: accounts.non_transferable_nft_master_edition.to_account_info(),
+    mint: accounts.non_transferable_nft_mint.to_account_info(),
+    authority: accounts.non_transferable_project.to_account_info(),
+    payer: accounts.authority.to_account_info(),
+    update_authority: accounts.non_transferable_project.to_account_info(),
+    system_program: accounts.system_program.to_account_info(),
+    sysvar_instructions: sysvar_instructions.to_account_info(),
+    spl_token_program: accounts.token_program.to_account_info(),
+  };
+  create_v1(
+    CpiContext::new(accounts.token_metadata_program.to_account_info(), cpi_accounts)
+      .with_signer(&[project_signer_seeds]),
+    asset_data,
+  )?;
+
+  let cpi_accounts = MintV1 {
+    token: accounts.user_token_account.to_account_info(),
+    token_owner: accounts.user_account.to_account_info(),
+    metadata: accounts.non_transferable_nft_metadata.to_account_info(),
+    master_edition: accounts.non_transferable_nft_master_edition.to_account_info(),
+    token_record: token_record.to_account_info(),
+    mint: accounts.non_transferable_nft_mint.to_account_info(),
+    authority: accounts.non_transferable_project.to_account_info(),
+    payer: accounts.authority.to_account_info(),
+    system_program: accounts.system_program.to_account_info(),
+    sysvar_instructions: sysvar_instructions.to_account_info(),
+    spl_token_program: accounts.token_program.to_account_info(),
+    spl_ata_program: accounts.associated_token_program.to_account_info(),
+    authorization_rules_program: authorization_rules_program.to_account_info(),
+    authorization_rules: authorization_rules.to_account_info(),
+  };
+  mint_v1(
+    CpiContext::new(accounts.token_metadata_program.to_account_info(), cpi_accounts)
+      .with_signer(&[project_signer_seeds]),
+  )?;
+
+  let cpi_accounts = VerifyCollectionV1 {
+    authority: accounts.non_transferable_project.to_account_info(),
+    metadata: accounts.non_transferable_nft_metadata.to_account_info(),
+    collection_mint: accounts.non_transferable_project_mint.to_account_info(),
+    collection_metadata: accounts.non_transferable_project_metadata.to_account_info(),
+    collection_master_edition: accounts.non_transferable_project_master_edition.to_account_info(),
+    system_program: accounts.system_program.to_account_info(),
+    sysvar_instructions: sysvar_instructions.to_account_info(),
+  };
+  verify_collection_v1(
+    CpiContext::new(accounts.token_metadata_program.to_account_info(), cpi_accounts)
+      .with_signer(&[project_signer_seeds]),
+  )?;
+
+  if sign_as_creator {
+    let cpi_accounts = VerifyCreatorV1 {
+      creator: accounts.authority.to_account_info(),
+      metadata: accounts.non_transferable_nft_metadata.to_account_info(),
+      system_program: accounts.system_program.to_account_info(),
+      sysvar_instructions: sysvar_instructions.to_account_info(),
+    };
+    verify_creator_v1(CpiContext::new(
+      accounts.token_metadata_program.to_account_info(),
+      cpi_accounts,
+    ))?;
+  }
+
+  Ok(())
+}
+
 pub fn handler(
   ctx: Context<VerifyContext>,
   rns_id: String,
   _wallet: Pubkey,
   merkle_root: String,
   _index: String,
+  sanctions_proof: Option<SanctionsProof>,
 ) -> Result<()> {
+  ctx.accounts.non_transferable_project.check_rns_id(&rns_id)?;
+  ctx
+    .accounts
+    .non_transferable_project
+    .check_sanctions(ctx.accounts.user_account.key(), &sanctions_proof)?;
+  let jurisdiction = ctx.accounts.non_transferable_user_status.jurisdiction;
+  ctx.accounts.non_transferable_project.check_attested_jurisdiction(jurisdiction)?;
+  let merkle_root = parse_merkle_root(&merkle_root)?;
+
+  let tier = ctx.accounts.tier.as_deref().map(|tier| &**tier);
+  require_keys_eq!(
+    ctx.accounts.non_transferable_project_mint.key(),
+    ctx.accounts.non_transferable_project.collection_mint(tier)?,
+    ErrorCode::TierMismatch
+  );
+
   let project_signer_seeds = [
     NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(),
     &[ctx.accounts.non_transferable_project.bump],
@@ -176,26 +368,41 @@ pub fn handler(
   // Check if the wallet is blacklisted
   let state = &ctx.accounts.non_transferable_project;
 
-  let creators = vec![
-    Creator {
-      address: ctx
-        .accounts
-        .non_transferable_project
-        .to_account_info()
-        .key(),
-      verified: true,
-      share: 0,
-    },
-    Creator {
-      address: ctx.accounts.authority.to_account_info().key(),
+  let mut creators = vec![Creator {
+    address: ctx
+      .accounts
+      .non_transferable_project
+      .to_account_info()
+      .key(),
+    verified: true,
+    share: 0,
+  }];
+  if state.creators.is_empty() {
+    creators.push(Creator {
+      address: ctx.accounts.authority.key(),
       verified: false,
       share: 100,
-    },
-  ];
+    });
+  } else {
+    creators.extend(state.creators.iter().map(|creator| Creator {
+      address: creator.address,
+      verified: false,
+      share: creator.share,
+    }));
+  }
+  // the authority signs this instruction, so it can verify itself as a creator
+  let sign_as_creator = creators
+    .iter()
+    .any(|creator| creator.address == ctx.accounts.authority.key());
 
-  let name = state.name.clone();
+  let name = state.metadata_name(&rns_id, ctx.accounts.non_transferable_nft_status.token_index)?;
   let symbol = state.symbol.clone();
-  let uri = state.base_uri.to_string() + &rns_id.to_string() + ".json";
+  let uri = state.metadata_uri(
+    tier,
+    &rns_id,
+    &ctx.accounts.non_transferable_nft_mint.key(),
+    &ctx.accounts.non_transferable_nft_status.key(),
+  )?;
 
   let data = DataV2 {
     name,
@@ -210,43 +417,98 @@ pub fn handler(
     uses: None,
   };
 
-  create_metadata_accounts_v3(
-    ctx
-      .accounts
-      .create_metadata_accounts_ctx()
-      .with_signer(&[&project_signer_seeds[..]]),
-    data,
-    true,
-    true,
-    None,
-  )?;
+  if state.programmable {
+    issue_programmable(&ctx, data, sign_as_creator, &project_signer_seeds)?;
+  } else {
+    create_metadata_accounts_v3(
+      ctx
+        .accounts
+        .create_metadata_accounts_ctx()
+        .with_signer(&[&project_signer_seeds[..]]),
+      data,
+      true,
+      true,
+      None,
+    )?;
 
-  msg!("verify_collection");
+    verify_sized_collection_item(
+      ctx
+        .accounts
+        .verify_collection_ctx()
+        .with_signer(&[&project_signer_seeds[..]]),
+      None,
+    )?;
 
-  verify_collection(
-    ctx
-      .accounts
-      .verify_collection_ctx()
-      .with_signer(&[&project_signer_seeds[..]]),
-    None,
-  )?;
+    if sign_as_creator {
+      let cpi_accounts = SignMetadata {
+        metadata: ctx.accounts.non_transferable_nft_metadata.to_account_info(),
+        creator: ctx.accounts.authority.to_account_info(),
+      };
+      sign_metadata(CpiContext::new(
+        ctx.accounts.token_metadata_program.to_account_info(),
+        cpi_accounts,
+      ))?;
+    }
+  }
+
+  let tier_number = match ctx.accounts.tier.as_mut() {
+    Some(tier) => {
+      tier.admit()?;
+      tier.tier
+    }
+    None => 0,
+  };
 
   let user_status = &mut ctx.accounts.non_transferable_user_status;
   require!(!user_status.is_minted, ErrorCode::LDIDHasMinted);
+  // issued in the tier that was paid for
+  require!(
+    !user_status.is_authorized || user_status.tier == tier_number,
+    ErrorCode::TierMismatch
+  );
+  user_status.tier = tier_number;
 
+  user_status.version = ACCOUNT_VERSION;
   user_status.is_minted = true;
+  if user_status.payer == Pubkey::default() {
+    // created here rather than by authorize_mint
+    user_status.payer = ctx.accounts.authority.key();
+  }
   user_status.authority = ctx.accounts.user_account.key();
-  user_status.rns_id = rns_id.clone();
+  user_status.rns_id_hash = rns_id_hash(&rns_id);
 
   let nft_status = &mut ctx.accounts.non_transferable_nft_status;
   nft_status.authority = ctx.accounts.user_account.key();
-  nft_status.merkle_root = merkle_root.clone();
+  nft_status.merkle_root = merkle_root;
+  nft_status.rns_id_hash = rns_id_hash(&rns_id);
   nft_status.rns_id = rns_id.clone();
   nft_status.mint = ctx.accounts.non_transferable_nft_mint.key();
+  nft_status.tier = tier_number;
+  nft_status.jurisdiction = jurisdiction;
+  if let Some(attributes) = ctx.accounts.non_transferable_nft_attributes.as_mut() {
+    if jurisdiction != [0; 2] {
+      attributes.jurisdiction = jurisdiction;
+    }
+  }
 
   let rns_id_status = &mut ctx.accounts.non_transferable_rns_id_status;
+  require!(
+    rns_id_status.has_room(state.max_wallets_per_rns_id),
+    ErrorCode::RnsIdWalletLimitReached
+  );
+  rns_id_status.version = ACCOUNT_VERSION;
   rns_id_status.authority = ctx.accounts.authority.key();
-  rns_id_status.num = rns_id_status.num + 1;
+  rns_id_status.num += 1;
 
-  Ok(())
+  let wallet_index = &mut ctx.accounts.non_transferable_wallet_index;
+  wallet_index.version = ACCOUNT_VERSION;
+  wallet_index.wallet = ctx.accounts.user_account.key();
+  wallet_index.bump = *ctx.bumps.get("non_transferable_wallet_index").unwrap();
+  wallet_index.upsert(
+    rns_id_hash(&rns_id),
+    ctx.accounts.non_transferable_nft_mint.key(),
+    DidStatus::Active,
+  )?;
+
+  release_payment(ctx.accounts)
 }
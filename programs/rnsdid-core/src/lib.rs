@@ -1,3 +1,6 @@
+// Anchor errors are large by design, every instruction returns them.
+#![allow(clippy::result_large_err)]
+
 use anchor_lang::prelude::*;
 
 pub mod error;
@@ -22,22 +25,30 @@ pub mod rnsdid_core {
   }
 
   pub fn set_mint_price(ctx: Context<SetMintPriceContext>, mint_price: u64) -> Result<()> {
-    let non_transferable_project = &mut ctx.accounts.non_transferable_project;
+    set_mint_price::handler(ctx, mint_price)
+  }
 
-    non_transferable_project.mint_price = mint_price;
+  pub fn schedule_mint_price(ctx: Context<ScheduleMintPriceContext>, mint_price: u64, effective_at: i64) -> Result<()> {
+    schedule_mint_price::handler(ctx, mint_price, effective_at)
+  }
 
-    msg!(
-      "SetMintPrice:collectionId:{}, price:{}",
-      non_transferable_project.to_account_info().key,
-      non_transferable_project.mint_price.to_string()
-    );
+  pub fn set_base_uri(ctx: Context<SetBaseURI>, uri: String) -> Result<()> {
+    let state = &mut ctx.accounts.non_transferable_project;
+    state.base_uri = uri;
+    Ok(())
+  }
 
+  pub fn set_uri_template(ctx: Context<SetUriTemplate>, uri_template: String) -> Result<()> {
+    require!(uri_template.len() <= MAX_URI_TEMPLATE_LEN, error::ErrorCode::UriTemplateTooLong);
+    let state = &mut ctx.accounts.non_transferable_project;
+    state.uri_template = uri_template;
     Ok(())
   }
 
-  pub fn set_base_uri(ctx: Context<SetBaseURI>, uri: String) -> Result<()> {
+  pub fn set_name_template(ctx: Context<SetNameTemplate>, name_template: String) -> Result<()> {
+    require!(name_template.len() <= MAX_NAME_TEMPLATE_LEN, error::ErrorCode::NameTemplateTooLong);
     let state = &mut ctx.accounts.non_transferable_project;
-    state.base_uri = uri;
+    state.name_template = name_template;
     Ok(())
   }
 
@@ -47,27 +58,106 @@ pub mod rnsdid_core {
     Ok(())
   }
 
+  pub fn set_referral_reward(ctx: Context<SetReferralReward>, reward_bps: u16) -> Result<()> {
+    require!(reward_bps as u64 <= MAX_DISCOUNT_BPS, error::ErrorCode::InvalidDiscount);
+    let state = &mut ctx.accounts.non_transferable_project;
+    state.referral_reward_bps = reward_bps;
+    Ok(())
+  }
+
+  pub fn set_authorization_ttl(ctx: Context<SetAuthorizationTtl>, ttl_slots: u64) -> Result<()> {
+    let state = &mut ctx.accounts.non_transferable_project;
+    state.authorization_ttl_slots = ttl_slots;
+    Ok(())
+  }
+
+  pub fn set_max_wallets_per_rns_id(ctx: Context<SetMaxWalletsPerRnsId>, max_wallets: u64) -> Result<()> {
+    let state = &mut ctx.accounts.non_transferable_project;
+    state.max_wallets_per_rns_id = max_wallets;
+    Ok(())
+  }
+
+  pub fn set_next_token_index(ctx: Context<SetNextTokenIndex>, next_token_index: u64) -> Result<()> {
+    let state = &mut ctx.accounts.non_transferable_project;
+    require!(
+      next_token_index >= state.next_token_index,
+      error::ErrorCode::InvalidTokenIndex
+    );
+    state.next_token_index = next_token_index;
+    Ok(())
+  }
+
+  pub fn set_privacy_mode(ctx: Context<SetPrivacyMode>, privacy_mode: bool) -> Result<()> {
+    let state = &mut ctx.accounts.non_transferable_project;
+    state.privacy_mode = privacy_mode;
+    Ok(())
+  }
+
+  pub fn set_creators(ctx: Context<SetCreators>, creators: Vec<CreatorShare>) -> Result<()> {
+    let project = ctx.accounts.non_transferable_project.key();
+    let state = &mut ctx.accounts.non_transferable_project;
+    state.set_creators(project, creators)
+  }
+
+  pub fn set_programmable_mode(ctx: Context<SetProgrammableMode>, programmable: bool) -> Result<()> {
+    let state = &mut ctx.accounts.non_transferable_project;
+    require!(
+      !programmable || state.rule_set != Pubkey::default(),
+      error::ErrorCode::RuleSetMissing
+    );
+    state.programmable = programmable;
+    Ok(())
+  }
+
+  pub fn set_jurisdiction_attestor(ctx: Context<SetJurisdictionAttestor>, attestor: Pubkey) -> Result<()> {
+    let state = &mut ctx.accounts.non_transferable_project;
+    state.jurisdiction_attestor = attestor;
+    Ok(())
+  }
+
+  pub fn set_commitment_attestor(ctx: Context<SetCommitmentAttestor>, attestor: Pubkey) -> Result<()> {
+    let state = &mut ctx.accounts.non_transferable_project;
+    state.commitment_attestor = attestor;
+    Ok(())
+  }
+
+  pub fn set_jurisdiction_denied(ctx: Context<SetJurisdictionDeniedContext>, jurisdiction: [u8; 2], is_denied: bool) -> Result<()> {
+    set_jurisdiction_denied::handler(ctx, jurisdiction, is_denied)
+  }
+
+  pub fn set_compliance_authority(ctx: Context<SetComplianceAuthority>, compliance_authority: Pubkey) -> Result<()> {
+    let state = &mut ctx.accounts.non_transferable_project;
+    state.compliance_authority = compliance_authority;
+    Ok(())
+  }
+
+  pub fn set_sanctions_root(ctx: Context<SetSanctionsRootContext>, sanctions_root: [u8; 32], wallets: u64) -> Result<()> {
+    set_sanctions_root::handler(ctx, sanctions_root, wallets)
+  }
+
+  pub fn set_allowlist_mode(ctx: Context<SetAllowlistModeContext>, is_enabled: bool) -> Result<()> {
+    set_allowlist_mode::handler(ctx, is_enabled)
+  }
+
+  pub fn add_allowlist_entry(ctx: Context<AddAllowlistEntryContext>, subject: [u8; 32]) -> Result<()> {
+    add_allowlist_entry::handler(ctx, subject)
+  }
+
+  pub fn remove_allowlist_entry(ctx: Context<RemoveAllowlistEntryContext>, subject: [u8; 32]) -> Result<()> {
+    remove_allowlist_entry::handler(ctx, subject)
+  }
+
+  pub fn set_allowlist_root(ctx: Context<SetAllowlistRootContext>, allowlist_root: [u8; 32], entries: u64) -> Result<()> {
+    set_allowlist_root::handler(ctx, allowlist_root, entries)
+  }
+
   pub fn set_is_blocked_address(
     ctx: Context<SetIsBlockedAddress>,
     wallet: Pubkey,
     is_blocked: bool,
   ) -> Result<()> {
     let state = &mut ctx.accounts.non_transferable_project;
-
-    let mut found = false;
-    for blocked_address in state.is_blocked_address.iter_mut() {
-      if blocked_address.key == wallet {
-        blocked_address.value = is_blocked;
-        found = true;
-        break;
-      }
-    }
-    if !found {
-      state.is_blocked_address.push(BlockedAddress {
-        key: wallet,
-        value: is_blocked,
-      });
-    }
+    state.set_blocked_address(wallet, is_blocked);
     Ok(())
   }
 
@@ -77,45 +167,112 @@ pub mod rnsdid_core {
     is_blocked: bool,
   ) -> Result<()> {
     let state = &mut ctx.accounts.non_transferable_project;
-
-    let mut found = false;
-    for blocked_rns_id in state.is_blocked_rns_id.iter_mut() {
-      if blocked_rns_id.key == rns_id {
-        blocked_rns_id.value = is_blocked;
-        found = true;
-        break;
-      }
-    }
-
-    if !found {
-      state.is_blocked_rns_id.push(BlockedRnsID {key: rns_id, value: is_blocked});
-    }
-
+    state.set_blocked_rns_id(rns_id, is_blocked);
     Ok(())
   }
 
   pub fn set_merkle_root(ctx: Context<SetMerkleRoot>, rns_id: String, merkle_root: String) -> Result<()> {
     let status = &mut ctx.accounts.non_transferable_nft_status;
-     require!(rns_id == status.rns_id, error::ErrorCode::RnsIsNotMatch);
-    status.merkle_root = merkle_root;
+    require!(status.matches_rns_id(&rns_id), error::ErrorCode::RnsIsNotMatch);
+    status.merkle_root = utils::parse_merkle_root(&merkle_root)?;
 
     Ok(())
   }
 
-  pub fn authorize_mint(ctx: Context<AuthorizeMintContext>, rns_id: String, wallet: Pubkey) -> Result<()> {
-    authorize_mint::handler(ctx, rns_id, wallet)
+  pub fn authorize_mint(ctx: Context<AuthorizeMintContext>, rns_id: String, wallet: Pubkey, args: AuthorizeMintArgs) -> Result<()> {
+    authorize_mint::handler(ctx, rns_id, wallet, args)
+  }
+
+  pub fn cancel_authorization(ctx: Context<CancelAuthorizationContext>, rns_id: String, wallet: Pubkey) -> Result<()> {
+    cancel_authorization::handler(ctx, rns_id, wallet)
   }
 
-  pub fn airdrop(ctx: Context<MintNonTransferableNft>, rns_id: String, wallet:Pubkey, merkle_root: String, index: String) -> Result<()> {
-    airdrop::handler(ctx, rns_id, wallet, merkle_root, index)
+  pub fn claim_referral_rewards(ctx: Context<ClaimReferralRewardsContext>) -> Result<()> {
+    claim_referral_rewards::handler(ctx)
   }
 
-  pub fn verify(ctx: Context<VerifyContext>, rns_id: String, wallet: Pubkey, merkle_root: String, index: String) -> Result<()> {
-    verify::handler(ctx, rns_id, wallet, merkle_root, index)
+  pub fn create_campaign(ctx: Context<CreateCampaignContext>, campaign_id: u64, args: CampaignArgs) -> Result<()> {
+    create_campaign::handler(ctx, campaign_id, args)
+  }
+
+  pub fn update_campaign(ctx: Context<UpdateCampaignContext>, campaign_id: u64, args: CampaignArgs) -> Result<()> {
+    update_campaign::handler(ctx, campaign_id, args)
+  }
+
+  pub fn create_tier(
+    ctx: Context<CreateTierContext>,
+    tier: u8,
+    collection: TierCollectionArgs,
+    args: TierArgs,
+  ) -> Result<()> {
+    create_tier::handler(ctx, tier, collection, args)
+  }
+
+  pub fn update_tier(ctx: Context<UpdateTierContext>, tier: u8, args: TierArgs) -> Result<()> {
+    update_tier::handler(ctx, tier, args)
+  }
+
+  pub fn upgrade_tier(ctx: Context<UpgradeTierContext>, tier: u8) -> Result<()> {
+    upgrade_tier::handler(ctx, tier)
+  }
+
+  pub fn airdrop(ctx: Context<MintNonTransferableNft>, rns_id: String, wallet:Pubkey, merkle_root: String, index: String, jurisdiction: Option<[u8; 2]>, sanctions_proof: Option<SanctionsProof>) -> Result<()> {
+    airdrop::handler(ctx, rns_id, wallet, merkle_root, index, jurisdiction, sanctions_proof)
+  }
+
+  pub fn verify(ctx: Context<VerifyContext>, rns_id: String, wallet: Pubkey, merkle_root: String, index: String, sanctions_proof: Option<SanctionsProof>) -> Result<()> {
+    verify::handler(ctx, rns_id, wallet, merkle_root, index, sanctions_proof)
   }
 
   pub fn burn(ctx: Context<BurnNonTransferableNft>, rns_id: String, wallet: Pubkey) -> Result<()> {
     burn::handler(ctx, rns_id, wallet)
   }
 
+  pub fn revoke(ctx: Context<RevokeContext>, rns_id: String, wallet: Pubkey) -> Result<()> {
+    revoke::handler(ctx, rns_id, wallet)
+  }
+
+  pub fn reissue(ctx: Context<ReissueContext>, rns_id: String, wallet: Pubkey) -> Result<()> {
+    reissue::handler(ctx, rns_id, wallet)
+  }
+
+  pub fn refresh_metadata<'info>(ctx: Context<'_, '_, '_, 'info, RefreshMetadataContext<'info>>) -> Result<()> {
+    refresh_metadata::handler(ctx)
+  }
+
+  pub fn update_collection_metadata(
+    ctx: Context<UpdateCollectionMetadataContext>,
+    args: CollectionMetadataArgs,
+  ) -> Result<()> {
+    update_collection_metadata::handler(ctx, args)
+  }
+
+  pub fn set_collection_size(ctx: Context<SetCollectionSizeContext>, size: u64) -> Result<()> {
+    set_collection_size::handler(ctx, size)
+  }
+
+  pub fn create_rule_set(ctx: Context<CreateRuleSetContext>) -> Result<()> {
+    create_rule_set::handler(ctx)
+  }
+
+  pub fn set_nft_attributes(ctx: Context<SetNftAttributesContext>, args: NftAttributesArgs) -> Result<()> {
+    set_nft_attributes::handler(ctx, args)
+  }
+
+  pub fn prove_rns_id(ctx: Context<ProveRnsIdContext>, rns_id: String, salt: String) -> Result<()> {
+    prove_rns_id::handler(ctx, rns_id, salt)
+  }
+
+  pub fn import_v1_project(ctx: Context<ImportV1ProjectContext>) -> Result<()> {
+    import_v1_project::handler(ctx)
+  }
+
+  pub fn exchange_v1_did(ctx: Context<ExchangeV1DidContext>, rns_id: String, wallet: Pubkey) -> Result<()> {
+    exchange_v1_did::handler(ctx, rns_id, wallet)
+  }
+
+  pub fn migrate_account(ctx: Context<MigrateAccountContext>, kind: AccountKind, wallet: Pubkey) -> Result<()> {
+    migrate_account::handler(ctx, kind, wallet)
+  }
+
 }
@@ -3,7 +3,13 @@ use sha2::{Digest, Sha256};
 
 use anchor_spl::token::Mint;
 
+use crate::error::ErrorCode;
+use crate::instructions::MintPriceChangedEvent;
+use crate::utils::{merkle_leaf, verify_merkle_proof};
+use mpl_token_metadata::state::{MAX_NAME_LENGTH, MAX_URI_LENGTH};
+
 pub const NON_TRANSFERABLE_PROJECT_PREFIX: &str = "nt-proj-v2";
+pub const NON_TRANSFERABLE_PROJECT_V1_PREFIX: &str = "nt-proj";
 pub const NON_TRANSFERABLE_PROJECT_MINT_PREFIX: &str = "nt-project-mint";
 pub const NON_TRANSFERABLE_PROJECT_VAULT_PREFIX: &str = "nt-project-mint-vault";
 
@@ -12,9 +18,28 @@ pub const NON_TRANSFERABLE_NFT_USERSTATUS_PREFIX: &str = "nt-nft-user-status";
 
 pub const NON_TRANSFERABLE_NFT_STATUS_PREFIX: &str = "nt-nft-status";
 pub const NON_TRANSFERABLE_NFT_RNSID_PREFIX: &str = "nt-nft-rnsid-status";
+
+pub const NON_TRANSFERABLE_CAMPAIGN_PREFIX: &str = "nt-campaign"; // campaign_id
+pub const NON_TRANSFERABLE_VOUCHER_PREFIX: &str = "nt-voucher-redemption"; // campaign_id + wallet
+pub const NON_TRANSFERABLE_REFERRAL_PREFIX: &str = "nt-referral"; // referrer wallet
+pub const NON_TRANSFERABLE_SPONSOR_RECEIPT_PREFIX: &str = "nt-sponsor-receipt"; // user status
+pub const NON_TRANSFERABLE_WALLET_INDEX_PREFIX: &str = "nt-wallet-index"; // wallet
+pub const NON_TRANSFERABLE_NFT_ATTRIBUTES_PREFIX: &str = "nt-nft-attributes"; // mint
+pub const NON_TRANSFERABLE_TIER_PREFIX: &str = "nt-tier"; // tier
+pub const NON_TRANSFERABLE_TIER_MINT_PREFIX: &str = "nt-tier-mint"; // tier
+pub const NON_TRANSFERABLE_TIER_VAULT_PREFIX: &str = "nt-tier-mint-vault"; // tier
+pub const NON_TRANSFERABLE_ALLOWLIST_PREFIX: &str = "nt-allowlist"; // wallet or rns_id hash
 pub const METADATA: &str = "metadata";
 
+/// Name of the token-auth-rules RuleSet owned by the project, used in its PDA.
+pub const NON_TRANSFERABLE_RULE_SET_NAME: &str = "rns-did-non-transferable";
+
+/// Layout version written as the first field of every account. Bump it together
+/// with a new arm in `migrate_account` whenever a layout changes.
+pub const ACCOUNT_VERSION: u8 = 1;
+
 pub const NON_TRANSFERABLE_PROJECT_SIZE: usize = 8 +
+  1 + // version
   100 + // name
   100 + // symbol
   100 + // base_uri
@@ -25,7 +50,34 @@ pub const NON_TRANSFERABLE_PROJECT_SIZE: usize = 8 +
   8 +  // mint_price
   32 +  // fee_recipient
   1 + // mint_bump
-  1; // bump
+  1 + // bump
+  2 + // referral_reward_bps
+  8 + // authorization_ttl_slots
+  8 + // pending_mint_price
+  8 + // pending_price_effective_at
+  8 + // next_token_index
+  8 + // max_wallets_per_rns_id
+  1 + // privacy_mode
+  4 + MAX_URI_TEMPLATE_LEN + // uri_template
+  1 + // programmable
+  32 + // rule_set
+  4 + MAX_PROJECT_CREATORS * (32 + 1) + // creators
+  4 + MAX_NAME_TEMPLATE_LEN + // name_template
+  32 + // jurisdiction_attestor
+  4 + MAX_DENIED_JURISDICTIONS * 2 + // denied_jurisdictions
+  32 + // compliance_authority
+  32 + // sanctions_root
+  1 + // allowlist_mode
+  32 + // allowlist_root
+  32; // commitment_attestor
+
+pub const MAX_URI_TEMPLATE_LEN: usize = 100;
+pub const MAX_NAME_TEMPLATE_LEN: usize = 64;
+pub const MAX_DENIED_JURISDICTIONS: usize = 64;
+/// Characters of the rns_id kept by `{rns_id_suffix}` in the name template.
+pub const RNS_ID_SUFFIX_LEN: usize = 6;
+/// Metaplex allows 5 creators, the project PDA always takes one of them.
+pub const MAX_PROJECT_CREATORS: usize = 4;
 
   #[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
 pub struct BlockedAddress {
@@ -39,6 +91,12 @@ pub struct BlockedRnsID {
     pub value: bool,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CreatorShare {
+    pub address: Pubkey,
+    pub share: u8,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
 pub struct TokenIdToMerkle {
     pub key: String,
@@ -48,6 +106,7 @@ pub struct TokenIdToMerkle {
 #[account]
 #[derive(Default)]
 pub struct ProjectAccount {
+  pub version: u8,
   pub authority: Pubkey,
   pub mint_price: u64,
   pub fee_recipient: Pubkey,
@@ -58,6 +117,47 @@ pub struct ProjectAccount {
   pub base_uri: String,
   pub is_blocked_address: Vec<BlockedAddress>,
   pub is_blocked_rns_id: Vec<BlockedRnsID>,
+  pub referral_reward_bps: u16,
+  pub authorization_ttl_slots: u64,
+  pub pending_mint_price: u64,
+  /// Unix timestamp at which `pending_mint_price` replaces `mint_price`, 0 if none is scheduled.
+  pub pending_price_effective_at: i64,
+  /// Only moves forward. DIDs minted before the counter used caller-chosen
+  /// indexes, `set_next_token_index` skips past them.
+  pub next_token_index: u64,
+  /// 0 means an rns_id can be issued to any number of wallets.
+  pub max_wallets_per_rns_id: u64,
+  /// Only salted rns_id commitments attested by `commitment_attestor` are
+  /// accepted, see `rns_id_commitment`.
+  pub privacy_mode: bool,
+  /// Replaces `base_uri + rns_id + ".json"` when set. `{mint}`, `{status}`,
+  /// `{rns_id}` and `{tier}` are substituted with the NFT mint, its status PDA,
+  /// the rns_id and the tier number.
+  pub uri_template: String,
+  /// Issues DIDs as programmable NFTs under `rule_set` instead of freezing them.
+  pub programmable: bool,
+  /// The RuleSet created by `create_rule_set`, default until then.
+  pub rule_set: Pubkey,
+  /// Creators of issued DIDs after the project PDA, empty for the authority at 100%.
+  pub creators: Vec<CreatorShare>,
+  /// Replaces `name` when set. `{name}`, `{index}`, `{rns_id}` and `{rns_id_suffix}`
+  /// are substituted with the project name, the token index, the rns_id and its
+  /// last `RNS_ID_SUFFIX_LEN` characters.
+  pub name_template: String,
+  /// Signs the jurisdiction attestations accepted by `authorize_mint`.
+  pub jurisdiction_attestor: Pubkey,
+  /// ISO 3166-1 alpha-2 codes of the jurisdictions DIDs can't be issued in.
+  pub denied_jurisdictions: Vec<[u8; 2]>,
+  /// May replace `sanctions_root` besides the authority.
+  pub compliance_authority: Pubkey,
+  /// Root of the sanctioned wallets, see `SanctionsProof`. Zeros turn screening off.
+  pub sanctions_root: [u8; 32],
+  /// Only allowlisted wallets and rns_ids can be authorized, see `check_allowlist`.
+  pub allowlist_mode: bool,
+  /// Root of the allowlisted wallets and rns_id hashes. Zeros accept no proof.
+  pub allowlist_root: [u8; 32],
+  /// Signs the commitments accepted by `authorize_mint` in privacy mode.
+  pub commitment_attestor: Pubkey,
 }
 
 impl ProjectAccount {
@@ -67,41 +167,669 @@ impl ProjectAccount {
   pub fn is_blocked_rns_id(&self, rns_id: String) -> bool {
     self.is_blocked_rns_id.iter().any(|pair| pair.key == rns_id && pair.value == true )
   }
+  /// Same as `is_blocked_rns_id`, for DIDs known only by their `rns_id_hash`.
+  pub fn is_blocked_rns_id_hash(&self, hash: &[u8; 32]) -> bool {
+    self.is_blocked_rns_id.iter().any(|pair| pair.value && rns_id_hash(&pair.key) == *hash)
+  }
+  pub fn set_blocked_address(&mut self, address: Pubkey, is_blocked: bool) {
+    match self.is_blocked_address.iter_mut().find(|pair| pair.key == address) {
+      Some(pair) => pair.value = is_blocked,
+      None => self.is_blocked_address.push(BlockedAddress { key: address, value: is_blocked }),
+    }
+  }
+  pub fn set_blocked_rns_id(&mut self, rns_id: String, is_blocked: bool) {
+    match self.is_blocked_rns_id.iter_mut().find(|pair| pair.key == rns_id) {
+      Some(pair) => pair.value = is_blocked,
+      None => self.is_blocked_rns_id.push(BlockedRnsID { key: rns_id, value: is_blocked }),
+    }
+  }
+  pub fn is_jurisdiction_denied(&self, jurisdiction: [u8; 2]) -> bool {
+    self.denied_jurisdictions.contains(&jurisdiction)
+  }
+  pub fn set_jurisdiction_denied(&mut self, jurisdiction: [u8; 2], is_denied: bool) -> Result<()> {
+    require!(is_jurisdiction_code(jurisdiction), ErrorCode::InvalidJurisdiction);
+    let position = self.denied_jurisdictions.iter().position(|code| *code == jurisdiction);
+    match (position, is_denied) {
+      (None, true) => {
+        require!(
+          self.denied_jurisdictions.len() < MAX_DENIED_JURISDICTIONS,
+          ErrorCode::JurisdictionDenylistFull
+        );
+        self.denied_jurisdictions.push(jurisdiction);
+      }
+      (Some(index), false) => {
+        self.denied_jurisdictions.swap_remove(index);
+      }
+      _ => {}
+    }
+    Ok(())
+  }
+  /// Rejects malformed codes and the denied jurisdictions.
+  pub fn check_jurisdiction(&self, jurisdiction: [u8; 2]) -> Result<()> {
+    require!(is_jurisdiction_code(jurisdiction), ErrorCode::InvalidJurisdiction);
+    require!(!self.is_jurisdiction_denied(jurisdiction), ErrorCode::JurisdictionDenied);
+    Ok(())
+  }
+  /// Re-checks the code attested in `authorize_mint` against the current
+  /// denylist, which can't be satisfied without one once codes are denied.
+  pub fn check_attested_jurisdiction(&self, jurisdiction: [u8; 2]) -> Result<()> {
+    if jurisdiction == [0; 2] {
+      require!(self.denied_jurisdictions.is_empty(), ErrorCode::JurisdictionRequired);
+      return Ok(());
+    }
+    self.check_jurisdiction(jurisdiction)
+  }
+  /// Jurisdiction a DID is issued under: the code attested in `authorize_mint`,
+  /// or the operator's for DIDs airdropped without one.
+  pub fn issuance_jurisdiction(&self, attested: [u8; 2], operator: Option<[u8; 2]>) -> Result<[u8; 2]> {
+    let jurisdiction = if attested == [0; 2] { operator.unwrap_or_default() } else { attested };
+    self.check_attested_jurisdiction(jurisdiction)?;
+    Ok(jurisdiction)
+  }
+  /// Requires a proof that `wallet` is not sanctioned while screening is on.
+  pub fn check_sanctions(&self, wallet: Pubkey, proof: &Option<SanctionsProof>) -> Result<()> {
+    if self.sanctions_root == [0; 32] {
+      return Ok(());
+    }
+    let Some(proof) = proof else {
+      return err!(ErrorCode::SanctionsProofRequired);
+    };
+    require!(
+      proof.low < wallet
+        && wallet < proof.high
+        && verify_merkle_proof(&proof.proof, &self.sanctions_root, proof.leaf()),
+      ErrorCode::InvalidSanctionsProof
+    );
+    Ok(())
+  }
+  /// In allowlist mode, requires `wallet` or `rns_id` to have an entry PDA or
+  /// a proof under `allowlist_root`.
+  pub fn check_allowlist(
+    &self,
+    wallet: Pubkey,
+    rns_id: &str,
+    entry: Option<&AllowlistEntryAccount>,
+    proof: &Option<Vec<[u8; 32]>>,
+  ) -> Result<()> {
+    if !self.allowlist_mode {
+      return Ok(());
+    }
+    let subjects = allowlist_subjects(wallet, rns_id);
+    let listed = entry.is_some_and(|entry| subjects.contains(&entry.subject));
+    let proven = self.allowlist_root != [0; 32]
+      && proof.as_ref().is_some_and(|proof| {
+        subjects
+          .iter()
+          .any(|subject| verify_merkle_proof(proof, &self.allowlist_root, merkle_leaf(subject)))
+      });
+    require!(listed || proven, ErrorCode::NotAllowlisted);
+    Ok(())
+  }
+  /// Shares must add up to 100. `project` is this account, the PDA is always
+  /// added in front and cannot be listed again.
+  pub fn set_creators(&mut self, project: Pubkey, creators: Vec<CreatorShare>) -> Result<()> {
+    require!(creators.len() <= MAX_PROJECT_CREATORS, ErrorCode::InvalidCreators);
+    let total: u16 = creators.iter().map(|c| c.share as u16).sum();
+    require!(creators.is_empty() || total == 100, ErrorCode::InvalidCreators);
+    for (i, creator) in creators.iter().enumerate() {
+      require!(
+        creator.address != project && creators[..i].iter().all(|c| c.address != creator.address),
+        ErrorCode::InvalidCreators
+      );
+    }
+    self.creators = creators;
+    Ok(())
+  }
+  /// Rejects rns_ids that don't fit the status accounts, and plaintext rns_ids
+  /// while the privacy mode is on.
+  pub fn check_rns_id(&self, rns_id: &str) -> Result<()> {
+    require!(rns_id.len() <= MAX_RNS_ID_LEN, ErrorCode::RnsIdTooLong);
+    if self.privacy_mode {
+      require!(is_rns_id_commitment(rns_id), ErrorCode::RnsIdNotCommitted);
+    }
+    Ok(())
+  }
+  /// URI written to the metadata of a newly verified DID, `tier` is the tier
+  /// it is issued in, if any.
+  pub fn metadata_uri(
+    &self,
+    tier: Option<&TierAccount>,
+    rns_id: &str,
+    mint: &Pubkey,
+    status: &Pubkey,
+  ) -> Result<String> {
+    // Migrated DIDs whose rns_id was too long to keep must keep their metadata.
+    require!(!rns_id.is_empty(), ErrorCode::RnsIdUnavailable);
+    let uri = if self.uri_template.is_empty() {
+      let base_uri = tier.map_or(&self.base_uri, |tier| &tier.base_uri);
+      base_uri.to_string() + rns_id + ".json"
+    } else {
+      self
+        .uri_template
+        .replace("{mint}", &mint.to_string())
+        .replace("{status}", &status.to_string())
+        .replace("{rns_id}", rns_id)
+        .replace("{tier}", &tier.map_or(0, |tier| tier.tier).to_string())
+    };
+    require!(uri.len() <= MAX_URI_LENGTH, ErrorCode::UriTooLong);
+    Ok(uri)
+  }
+  /// Name written to the metadata of a newly verified DID.
+  pub fn metadata_name(&self, rns_id: &str, token_index: u64) -> Result<String> {
+    require!(!rns_id.is_empty(), ErrorCode::RnsIdUnavailable);
+    let name = if self.name_template.is_empty() {
+      self.name.clone()
+    } else {
+      let suffix_start = rns_id
+        .char_indices()
+        .rev()
+        .nth(RNS_ID_SUFFIX_LEN - 1)
+        .map_or(0, |(i, _)| i);
+      self
+        .name_template
+        .replace("{name}", &self.name)
+        .replace("{index}", &token_index.to_string())
+        .replace("{rns_id_suffix}", &rns_id[suffix_start..])
+        .replace("{rns_id}", rns_id)
+    };
+    require!(name.len() <= MAX_NAME_LENGTH, ErrorCode::NameTooLong);
+    Ok(name)
+  }
+  /// Collection DIDs of `tier` are verified in, the project collection if none.
+  pub fn collection_mint(&self, tier: Option<&TierAccount>) -> Result<Pubkey> {
+    match tier {
+      Some(tier) => Ok(tier.mint),
+      None => Pubkey::create_program_address(
+        &[NON_TRANSFERABLE_PROJECT_MINT_PREFIX.as_bytes(), &[self.mint_bump]],
+        &crate::ID,
+      )
+      .map_err(|_| error!(ErrorCode::TierMismatch)),
+    }
+  }
+  /// Promotes the scheduled price once it is due and returns the price in force.
+  pub fn apply_pending_mint_price(&mut self, now: i64) -> u64 {
+    if self.pending_price_effective_at != 0 && now >= self.pending_price_effective_at {
+      emit!(MintPriceChangedEvent {
+        previous_price: self.mint_price,
+        mint_price: self.pending_mint_price,
+      });
+      self.mint_price = self.pending_mint_price;
+      self.pending_mint_price = 0;
+      self.pending_price_effective_at = 0;
+    }
+    self.mint_price
+  }
+  pub fn referral_reward(&self, amount: u64) -> u64 {
+    (amount as u128 * self.referral_reward_bps as u128 / MAX_DISCOUNT_BPS as u128) as u64
+  }
+}
+
+/// ISO 3166-1 alpha-2 codes are two uppercase letters.
+pub fn is_jurisdiction_code(jurisdiction: [u8; 2]) -> bool {
+  jurisdiction.iter().all(u8::is_ascii_uppercase)
 }
 
 pub const NON_TRANSFERABLE_USER_PAY: &str = "nt-nft-user-pay";
 
+/// Longest rns_id accepted by the instructions and kept for display.
+pub const MAX_RNS_ID_LEN: usize = 64;
+
+pub const NON_TRANSFERABLE_USER_STATUS_SIZE: usize = 8 +
+  1 +  // version
+  32 + // authority
+  32 + // rns_id_hash
+  1 +  // is_minted
+  1 +  // is_authorized
+  1 +  // bump
+  32 + // payer
+  8 +  // paid_amount
+  8 +  // authorization_expiry_slot
+  1 +  // tier
+  32 + // referrer
+  8 +  // referral_reward
+  2;   // jurisdiction
+
+pub const NON_TRANSFERABLE_CAMPAIGN_SIZE: usize = 8 +
+  1 +  // version
+  8 +  // campaign_id
+  1 +  // kind
+  32 + // issuer
+  32 + // merkle_root
+  1 +  // discount_kind
+  8 +  // discount_value
+  8 +  // max_uses
+  8 +  // used
+  1 +  // is_active
+  1;   // bump
+
+pub const NON_TRANSFERABLE_VOUCHER_REDEMPTION_SIZE: usize = 8 +
+  1 +  // version
+  32 + // campaign
+  32 + // wallet
+  8 +  // discount
+  1;   // bump
+
+/// Percentages are expressed in basis points.
+pub const MAX_DISCOUNT_BPS: u64 = 10_000;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VoucherKind {
+  /// The voucher message is signed by the campaign issuer (ed25519 instruction).
+  #[default]
+  Signature,
+  /// The wallet proves membership against the campaign merkle root.
+  Merkle,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiscountKind {
+  #[default]
+  Percentage,
+  Absolute,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct DiscountVoucher {
+  pub campaign_id: u64,
+  pub proof: Vec<[u8; 32]>,
+}
+
+/// Proves a wallet is not on the sanctions list. The list is sorted, starts
+/// with the all-zero key and ends with the all-0xff key, and each leaf hashes
+/// two neighbours of it: a wallet strictly between the neighbours of a leaf
+/// can't be listed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct SanctionsProof {
+  pub low: Pubkey,
+  pub high: Pubkey,
+  pub proof: Vec<[u8; 32]>,
+}
+
+impl SanctionsProof {
+  pub fn leaf(&self) -> [u8; 32] {
+    merkle_leaf(&[self.low.as_ref(), self.high.as_ref()].concat())
+  }
+}
+
+impl DiscountVoucher {
+  /// Seed used to derive the campaign and redemption PDAs of an optional voucher.
+  pub fn campaign_seed(voucher: &Option<DiscountVoucher>) -> [u8; 8] {
+    voucher.as_ref().map(|v| v.campaign_id).unwrap_or_default().to_le_bytes()
+  }
+}
+
+#[account]
+#[derive(Default)]
+pub struct CampaignAccount {
+  pub version: u8,
+  pub campaign_id: u64,
+  pub kind: VoucherKind,
+  pub issuer: Pubkey,
+  pub merkle_root: [u8; 32],
+  pub discount_kind: DiscountKind,
+  pub discount_value: u64,
+  pub max_uses: u64, // 0 means unlimited
+  pub used: u64,
+  pub is_active: bool,
+  pub bump: u8,
+}
+
+impl CampaignAccount {
+  pub fn is_exhausted(&self) -> bool {
+    self.max_uses != 0 && self.used >= self.max_uses
+  }
+
+  /// Returns the discount granted on `price`, never more than the price itself.
+  pub fn discount_for(&self, price: u64) -> u64 {
+    let discount = match self.discount_kind {
+      DiscountKind::Percentage => {
+        (price as u128 * self.discount_value as u128 / MAX_DISCOUNT_BPS as u128) as u64
+      }
+      DiscountKind::Absolute => self.discount_value,
+    };
+    discount.min(price)
+  }
+}
+
+pub const NON_TRANSFERABLE_TIER_SIZE: usize = 8 +
+  1 +  // version
+  1 +  // tier
+  32 + // mint
+  4 + MAX_URI_LENGTH + // base_uri
+  8 +  // mint_price
+  8 +  // max_supply
+  8 +  // issued
+  1 +  // is_active
+  1 +  // mint_bump
+  1;   // bump
+
+/// A credential level with its own collection. Tiers are numbered from 1, tier
+/// 0 stands for the project collection and its settings.
+#[account]
+#[derive(Default)]
+pub struct TierAccount {
+  pub version: u8,
+  pub tier: u8,
+  pub mint: Pubkey,
+  /// Replaces the project `base_uri` for DIDs of this tier.
+  pub base_uri: String,
+  pub mint_price: u64,
+  pub max_supply: u64, // 0 means unlimited
+  /// DIDs issued in or upgraded to this tier, burns don't free room.
+  pub issued: u64,
+  /// Inactive tiers accept neither new authorizations, issuance nor upgrades.
+  pub is_active: bool,
+  pub mint_bump: u8,
+  pub bump: u8,
+}
+
+impl TierAccount {
+  /// Checks that a DID can still enter the tier.
+  pub fn check_open(&self) -> Result<()> {
+    require!(self.is_active, ErrorCode::TierInactive);
+    require!(self.max_supply == 0 || self.issued < self.max_supply, ErrorCode::TierSoldOut);
+    Ok(())
+  }
+
+  /// Counts a DID entering the tier.
+  pub fn admit(&mut self) -> Result<()> {
+    self.check_open()?;
+    self.issued += 1;
+    Ok(())
+  }
+}
+
+pub const NON_TRANSFERABLE_ALLOWLIST_ENTRY_SIZE: usize = 8 +
+  1 +  // version
+  32 + // subject
+  1;   // bump
+
+/// Allows a wallet or an rns_id while the project is in allowlist mode.
+#[account]
+#[derive(Default)]
+pub struct AllowlistEntryAccount {
+  pub version: u8,
+  /// The wallet key, or the `rns_id_hash` of the rns_id.
+  pub subject: [u8; 32],
+  pub bump: u8,
+}
+
+pub const NON_TRANSFERABLE_REFERRAL_STATS_SIZE: usize = 8 +
+  1 +  // version
+  32 + // referrer
+  8 +  // referrals
+  8 +  // pending_rewards
+  8 +  // total_rewards
+  1;   // bump
+
+pub const NON_TRANSFERABLE_SPONSOR_RECEIPT_SIZE: usize = 8 +
+  1 +  // version
+  32 + // sponsor
+  32 + // beneficiary
+  32 + // user_status
+  8 +  // amount
+  8 +  // timestamp
+  1;   // bump
+
+/// Issued to whoever paid `authorize_mint` on behalf of another wallet.
+#[account]
+#[derive(Default)]
+pub struct SponsorReceiptAccount {
+  pub version: u8,
+  pub sponsor: Pubkey,
+  pub beneficiary: Pubkey,
+  pub user_status: Pubkey,
+  pub amount: u64,
+  pub timestamp: i64,
+  pub bump: u8,
+}
+
+/// Referral rewards are escrowed as lamports on this account until claimed.
+#[account]
+#[derive(Default)]
+pub struct ReferralStatsAccount {
+  pub version: u8,
+  pub referrer: Pubkey,
+  pub referrals: u64,
+  pub pending_rewards: u64,
+  pub total_rewards: u64,
+  pub bump: u8,
+}
+
+#[account]
+#[derive(Default)]
+pub struct VoucherRedemptionAccount {
+  pub version: u8,
+  pub campaign: Pubkey,
+  pub wallet: Pubkey,
+  pub discount: u64,
+  pub bump: u8,
+}
+
 #[account]
 #[derive(Default)]
 pub struct UserStatusAccount {
+  pub version: u8,
   pub authority: Pubkey,
-  pub rns_id: String,
+  pub rns_id_hash: [u8; 32],
 
   pub is_minted: bool,
   pub is_authorized: bool,
   pub bump: u8,
+
+  pub payer: Pubkey,
+  /// Lamports of the current authorization escrowed on this account for the
+  /// fee recipient, paid out by `verify` or refunded on cancellation.
+  pub paid_amount: u64,
+  /// 0 means the authorization never expires.
+  pub authorization_expiry_slot: u64,
+  /// Tier paid for by `authorize_mint`, 0 for the project collection.
+  pub tier: u8,
+  pub referrer: Pubkey,
+  /// Lamports of the payment escrowed for `referrer`, paid out or refunded with `paid_amount`.
+  pub referral_reward: u64,
+  /// ISO 3166-1 alpha-2 code attested in `authorize_mint`, zeros if none was.
+  pub jurisdiction: [u8; 2],
+}
+
+impl UserStatusAccount {
+  pub fn is_authorization_expired(&self, slot: u64) -> bool {
+    self.is_authorized
+      && !self.is_minted
+      && self.authorization_expiry_slot != 0
+      && slot > self.authorization_expiry_slot
+  }
 }
 
+pub const NON_TRANSFERABLE_NFT_STATUS_SIZE: usize = 8 +
+  1 +  // version
+  32 + // authority
+  1 +  // bump
+  32 + // rns_id_hash
+  4 + MAX_RNS_ID_LEN + // rns_id
+  32 + // merkle_root
+  32 + // mint
+  8 +  // token_index
+  1 +  // is_burned
+  32 + // previous_mint
+  32 + // reissued_to
+  32 + // payer
+  1 +  // is_revoked
+  1 +  // tier
+  2;   // jurisdiction
+
 #[account]
 #[derive(Default)]
 pub struct NftStatusAccount {
+  pub version: u8,
   pub authority: Pubkey,
 
   pub bump: u8,
 
+  pub rns_id_hash: [u8; 32],
+  /// Display copy of the rns_id, the hash is authoritative.
   pub rns_id: String,
-  pub merkle_root: String,
+  pub merkle_root: [u8; 32],
   pub mint: Pubkey,
+  pub token_index: u64,
+
+  /// Burned statuses are kept so a reissued DID can point back to them.
+  pub is_burned: bool,
+  pub previous_mint: Pubkey,
+  pub reissued_to: Pubkey,
+
+  /// Paid the rent of the NFT accounts at issuance and gets it back on burn.
+  pub payer: Pubkey,
+
+  pub is_revoked: bool,
+
+  /// Tier whose collection the DID is verified in, 0 for the project collection.
+  pub tier: u8,
+  /// ISO 3166-1 alpha-2 code the DID was issued under, zeros if none.
+  pub jurisdiction: [u8; 2],
 }
 
+impl NftStatusAccount {
+  pub fn is_active(&self) -> bool {
+    !self.is_burned && !self.is_revoked
+  }
+
+  pub fn matches_rns_id(&self, rns_id: &str) -> bool {
+    self.rns_id_hash == rns_id_hash(rns_id)
+  }
+
+  pub fn did_status(&self) -> DidStatus {
+    if self.is_burned {
+      DidStatus::Burned
+    } else if self.is_revoked {
+      DidStatus::Revoked
+    } else {
+      DidStatus::Active
+    }
+  }
+}
+
+
+pub const NON_TRANSFERABLE_RNS_ID_STATUS_SIZE: usize = 8 +
+  1 +  // version
+  32 + // authority
+  8;   // num
 
 #[account]
 #[derive(Default)]
 pub struct RnsIdStatusAccount {
+  pub version: u8,
   pub authority: Pubkey,
   pub num: u64,
 }
 
+impl RnsIdStatusAccount {
+  pub fn has_room(&self, max_wallets: u64) -> bool {
+    max_wallets == 0 || self.num < max_wallets
+  }
+}
+
+pub const MAX_WALLET_INDEX_ENTRIES: usize = 16;
+
+pub const NON_TRANSFERABLE_WALLET_INDEX_SIZE: usize = 8 +
+  1 +  // version
+  32 + // wallet
+  1 +  // bump
+  4 + MAX_WALLET_INDEX_ENTRIES * (
+    32 + // rns_id_hash
+    32 + // mint
+    1    // status
+  );
+
+pub const NON_TRANSFERABLE_NFT_ATTRIBUTES_SIZE: usize = 8 +
+  1 +  // version
+  32 + // mint
+  1 +  // kyc_level
+  32 + // issuer
+  8 +  // issued_at
+  8 +  // expires_at
+  2 +  // jurisdiction
+  1 +  // status
+  1;   // bump
+
+/// On-chain attributes of a DID, so its metadata can be rendered from account
+/// state instead of hosted JSON.
+#[account]
+#[derive(Default)]
+pub struct NftAttributesAccount {
+  pub version: u8,
+  pub mint: Pubkey,
+  pub kyc_level: u8,
+  pub issuer: Pubkey,
+  /// When the attributes were last set.
+  pub issued_at: i64,
+  /// 0 means the DID never expires.
+  pub expires_at: i64,
+  /// ISO 3166-1 alpha-2 code, zeros if unknown.
+  pub jurisdiction: [u8; 2],
+  pub status: DidStatus,
+  pub bump: u8,
+}
+
+/// Account types that `migrate_account` can move to the latest layout.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AccountKind {
+  Project,
+  UserStatus,
+  NftStatus,
+  RnsIdStatus,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DidStatus {
+  #[default]
+  Active,
+  Revoked,
+  Burned,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct WalletIndexEntry {
+  pub rns_id_hash: [u8; 32],
+  pub mint: Pubkey,
+  pub status: DidStatus,
+}
+
+/// Lists the DIDs a wallet has held, so holders can be checked with one fetch.
+#[account]
+#[derive(Default)]
+pub struct WalletIndexAccount {
+  pub version: u8,
+  pub wallet: Pubkey,
+  pub bump: u8,
+  pub entries: Vec<WalletIndexEntry>,
+}
+
+impl WalletIndexAccount {
+  pub fn is_holder(&self) -> bool {
+    self.entries.iter().any(|entry| entry.status == DidStatus::Active)
+  }
+
+  /// Updates the entry of `mint`, or adds one. A full index recycles the slot
+  /// of a burned DID.
+  pub fn upsert(&mut self, rns_id_hash: [u8; 32], mint: Pubkey, status: DidStatus) -> Result<()> {
+    let entry = WalletIndexEntry { rns_id_hash, mint, status };
+    if let Some(existing) = self.entries.iter_mut().find(|e| e.mint == mint) {
+      *existing = entry;
+    } else if self.entries.len() < MAX_WALLET_INDEX_ENTRIES {
+      self.entries.push(entry);
+    } else {
+      let burned = self
+        .entries
+        .iter_mut()
+        .find(|e| e.status == DidStatus::Burned)
+        .ok_or(ErrorCode::WalletIndexFull)?;
+      *burned = entry;
+    }
+    Ok(())
+  }
+}
+
 #[derive(Accounts)]
 pub struct SetBaseURI<'info> {
     #[account(mut, has_one = authority)]
@@ -117,6 +845,90 @@ pub struct SetFeeRecipient<'info> {
 }
 
 
+#[derive(Accounts)]
+pub struct SetReferralReward<'info> {
+    #[account(mut, has_one = authority)]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetAuthorizationTtl<'info> {
+    #[account(mut, has_one = authority)]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetNextTokenIndex<'info> {
+    #[account(mut, has_one = authority)]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetMaxWalletsPerRnsId<'info> {
+    #[account(mut, has_one = authority)]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetUriTemplate<'info> {
+    #[account(mut, has_one = authority)]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetComplianceAuthority<'info> {
+    #[account(mut, has_one = authority)]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetJurisdictionAttestor<'info> {
+    #[account(mut, has_one = authority)]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCommitmentAttestor<'info> {
+    #[account(mut, has_one = authority)]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetNameTemplate<'info> {
+    #[account(mut, has_one = authority)]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPrivacyMode<'info> {
+    #[account(mut, has_one = authority)]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCreators<'info> {
+    #[account(mut, has_one = authority)]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetProgrammableMode<'info> {
+    #[account(mut, has_one = authority)]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+    pub authority: Signer<'info>,
+}
+
 #[derive(Accounts)]
 pub struct SetIsBlockedAddress<'info> {
     #[account(mut, has_one = authority)]
@@ -162,18 +974,34 @@ pub struct SetTokenIdToMerkle<'info> {
 }
 
 
-#[derive(Accounts)]
-pub struct SetMintPriceContext<'info> {
-  #[account()]
-  pub authority: Signer<'info>,
-  #[account(mut, has_one = authority)]
-  pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
-}
-
 
 
 pub fn hash_seed(seed: &str) -> Vec<u8> {
   let mut hasher = Sha256::new();
   hasher.update(seed.as_bytes());
   hasher.finalize().to_vec()
+}
+
+pub fn rns_id_hash(rns_id: &str) -> [u8; 32] {
+  Sha256::digest(rns_id.as_bytes()).into()
+}
+
+/// Allowlist subjects an authorization can match: the wallet, then the rns_id.
+pub fn allowlist_subjects(wallet: Pubkey, rns_id: &str) -> [[u8; 32]; 2] {
+  [wallet.to_bytes(), rns_id_hash(rns_id)]
+}
+
+/// Commitment used in place of the rns_id in privacy mode: the hex-encoded
+/// `hash_seed` of the holder's salt followed by the rns_id. The operator derives
+/// a single salt per rns_id, so the wallet limit, blocklist and allowlist keyed
+/// on the commitment still apply to the rns_id behind it.
+pub fn rns_id_commitment(rns_id: &str, salt: &str) -> String {
+  hash_seed(&format!("{}{}", salt, rns_id))
+    .iter()
+    .map(|b| format!("{:02x}", b))
+    .collect()
+}
+
+pub fn is_rns_id_commitment(rns_id: &str) -> bool {
+  rns_id.len() == 64 && rns_id.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
 }
\ No newline at end of file
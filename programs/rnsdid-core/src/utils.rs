@@ -1,8 +1,28 @@
-use anchor_lang::{prelude::*, solana_program};
+use anchor_lang::{
+  prelude::*,
+  solana_program::{
+    self, ed25519_program,
+    sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+  },
+};
+use mpl_token_auth_rules::instruction::{
+  builders::CreateOrUpdateBuilder, CreateOrUpdateArgs,
+  InstructionBuilder as RuleSetInstructionBuilder,
+};
 use mpl_token_metadata::{
-  state::{CollectionDetails, DataV2},
+  instruction::{
+    builders::{
+      BurnBuilder, CreateBuilder, MintBuilder, UnverifyBuilder, UpdateBuilder, VerifyBuilder,
+    },
+    BurnArgs, CollectionToggle, CreateArgs, InstructionBuilder, MintArgs, UpdateArgs,
+    VerificationArgs,
+  },
+  state::{AssetData, Collection, CollectionDetails, Data, DataV2, PrintSupply},
   ID,
 };
+use sha2::{Digest, Sha256};
+
+use crate::error::ErrorCode;
 
 #[derive(Accounts)]
 pub struct CreateMetadataAccountsV3<'info> {
@@ -133,11 +153,35 @@ pub struct VerifyCollection<'info> {
   pub collection_master_edition: AccountInfo<'info>,
 }
 
-pub fn verify_collection<'info>(
+/// Verifies the item and increments the size of the sized collection.
+pub fn verify_sized_collection_item<'info>(
+  ctx: CpiContext<'_, '_, '_, 'info, VerifyCollection<'info>>,
+  collection_authority_record: Option<Pubkey>,
+) -> Result<()> {
+  let ix = mpl_token_metadata::instruction::verify_sized_collection_item(
+    ID,
+    *ctx.accounts.metadata.key,
+    *ctx.accounts.collection_authority.key,
+    *ctx.accounts.payer.key,
+    *ctx.accounts.collection_mint.key,
+    *ctx.accounts.collection_metadata.key,
+    *ctx.accounts.collection_master_edition.key,
+    collection_authority_record,
+  );
+  solana_program::program::invoke_signed(
+    &ix,
+    &ToAccountInfos::to_account_infos(&ctx),
+    ctx.signer_seeds,
+  )
+  .map_err(Into::into)
+}
+
+/// Unverifies the item and decrements the size of the sized collection.
+pub fn unverify_sized_collection_item<'info>(
   ctx: CpiContext<'_, '_, '_, 'info, VerifyCollection<'info>>,
   collection_authority_record: Option<Pubkey>,
 ) -> Result<()> {
-  let ix = mpl_token_metadata::instruction::verify_collection(
+  let ix = mpl_token_metadata::instruction::unverify_sized_collection_item(
     ID,
     *ctx.accounts.metadata.key,
     *ctx.accounts.collection_authority.key,
@@ -154,3 +198,673 @@ pub fn verify_collection<'info>(
   )
   .map_err(Into::into)
 }
+
+/// Moves the item to another sized collection and verifies it there. The
+/// collection authority must also be the update authority of the item.
+pub fn set_and_verify_sized_collection_item<'info>(
+  ctx: CpiContext<'_, '_, '_, 'info, VerifyCollection<'info>>,
+  collection_authority_record: Option<Pubkey>,
+) -> Result<()> {
+  let ix = mpl_token_metadata::instruction::set_and_verify_sized_collection_item(
+    ID,
+    *ctx.accounts.metadata.key,
+    *ctx.accounts.collection_authority.key,
+    *ctx.accounts.payer.key,
+    *ctx.accounts.collection_authority.key,
+    *ctx.accounts.collection_mint.key,
+    *ctx.accounts.collection_metadata.key,
+    *ctx.accounts.collection_master_edition.key,
+    collection_authority_record,
+  );
+  solana_program::program::invoke_signed(
+    &ix,
+    &ToAccountInfos::to_account_infos(&ctx),
+    ctx.signer_seeds,
+  )
+  .map_err(Into::into)
+}
+
+/// Decodes a hex-encoded merkle root. An empty string clears the root.
+pub fn parse_merkle_root(merkle_root: &str) -> Result<[u8; 32]> {
+  let mut root = [0u8; 32];
+  if merkle_root.is_empty() {
+    return Ok(root);
+  }
+  require!(
+    merkle_root.len() == 64 && merkle_root.bytes().all(|b| b.is_ascii_hexdigit()),
+    ErrorCode::InvalidMerkleRoot
+  );
+  for (i, byte) in root.iter_mut().enumerate() {
+    *byte = u8::from_str_radix(&merkle_root[i * 2..i * 2 + 2], 16).unwrap();
+  }
+  Ok(root)
+}
+
+// Leaves and internal nodes are hashed under distinct prefixes, so an internal
+// node can't be passed off as a leaf.
+const MERKLE_LEAF_PREFIX: u8 = 0x00;
+const MERKLE_NODE_PREFIX: u8 = 0x01;
+
+/// Hashes a node pair in sorted order, so proofs don't need to carry positions.
+pub fn hash_sorted_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+  let mut hasher = Sha256::new();
+  hasher.update([MERKLE_NODE_PREFIX]);
+  if a <= b {
+    hasher.update(a);
+    hasher.update(b);
+  } else {
+    hasher.update(b);
+    hasher.update(a);
+  }
+  hasher.finalize().into()
+}
+
+pub fn merkle_leaf(data: &[u8]) -> [u8; 32] {
+  let mut hasher = Sha256::new();
+  hasher.update([MERKLE_LEAF_PREFIX]);
+  hasher.update(data);
+  hasher.finalize().into()
+}
+
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+  let computed = proof
+    .iter()
+    .fold(leaf, |node, sibling| hash_sorted_pair(&node, sibling));
+  computed == *root
+}
+
+// Offsets of the single signature header inside ed25519 program instruction data.
+const ED25519_HEADER_LEN: usize = 16;
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+  data
+    .get(offset..offset + 2)
+    .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+    .ok_or_else(|| error!(ErrorCode::InvalidDataProvided))
+}
+
+/// Checks that one of the ed25519 program instructions right before the current
+/// one verifies `message` signed by `signer`. Each signature a caller needs, such
+/// as a voucher and a jurisdiction attestation, gets its own ed25519 instruction.
+pub fn verify_ed25519_instruction(
+  instructions: &AccountInfo,
+  signer: &Pubkey,
+  message: &[u8],
+) -> Result<()> {
+  let mut index = load_current_index_checked(instructions)? as usize;
+  let mut found = false;
+  while index > 0 {
+    index -= 1;
+    let ix = load_instruction_at_checked(index, instructions)?;
+    if ix.program_id != ed25519_program::ID {
+      break;
+    }
+    require!(ix.accounts.is_empty(), ErrorCode::InvalidDataProvided);
+    found = true;
+
+    let (public_key, signed_message) = ed25519_signature(&ix.data)?;
+    if public_key == signer.as_ref() && signed_message == message {
+      return Ok(());
+    }
+  }
+  require!(found, ErrorCode::InvalidDataProvided);
+
+  err!(ErrorCode::SignatureVerificationFailed)
+}
+
+/// Reads the public key and message of a single-signature ed25519 instruction.
+fn ed25519_signature(data: &[u8]) -> Result<(&[u8], &[u8])> {
+  require!(
+    data.len() >= ED25519_HEADER_LEN && data[0] == 1,
+    ErrorCode::InvalidDataProvided
+  );
+
+  let public_key_offset = read_u16(data, 6)? as usize;
+  let message_offset = read_u16(data, 10)? as usize;
+  let message_size = read_u16(data, 12)? as usize;
+
+  // All offsets must point into the ed25519 instruction itself.
+  for index_offset in [4, 8, 14] {
+    require!(
+      read_u16(data, index_offset)? == u16::MAX,
+      ErrorCode::InvalidDataProvided
+    );
+  }
+
+  let public_key = data
+    .get(public_key_offset..public_key_offset + 32)
+    .ok_or(ErrorCode::InvalidDataProvided)?;
+  let signed_message = data
+    .get(message_offset..message_offset + message_size)
+    .ok_or(ErrorCode::InvalidDataProvided)?;
+
+  Ok((public_key, signed_message))
+}
+
+#[derive(Accounts)]
+pub struct BurnNft<'info> {
+  /// CHECK: Used in CPI So no Harm
+  pub metadata: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub owner: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub mint: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub token_account: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub master_edition: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub spl_token: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub collection_metadata: AccountInfo<'info>,
+}
+
+/// Burns the token and closes the token, metadata and edition accounts,
+/// sending their rent to the owner.
+pub fn burn_nft<'info>(ctx: CpiContext<'_, '_, '_, 'info, BurnNft<'info>>) -> Result<()> {
+  let ix = mpl_token_metadata::instruction::burn_nft(
+    ID,
+    *ctx.accounts.metadata.key,
+    *ctx.accounts.owner.key,
+    *ctx.accounts.mint.key,
+    *ctx.accounts.token_account.key,
+    *ctx.accounts.master_edition.key,
+    *ctx.accounts.spl_token.key,
+    Some(*ctx.accounts.collection_metadata.key),
+  );
+  solana_program::program::invoke_signed(
+    &ix,
+    &ToAccountInfos::to_account_infos(&ctx),
+    ctx.signer_seeds,
+  )
+  .map_err(Into::into)
+}
+
+#[derive(Accounts)]
+pub struct UpdateMetadataAccountsV2<'info> {
+  /// CHECK: Used in CPI So no Harm
+  pub metadata: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub update_authority: AccountInfo<'info>,
+}
+
+pub fn update_metadata_accounts_v2<'info>(
+  ctx: CpiContext<'_, '_, '_, 'info, UpdateMetadataAccountsV2<'info>>,
+  data: DataV2,
+) -> Result<()> {
+  let ix = mpl_token_metadata::instruction::update_metadata_accounts_v2(
+    ID,
+    *ctx.accounts.metadata.key,
+    *ctx.accounts.update_authority.key,
+    None,
+    Some(data),
+    None,
+    None,
+  );
+  solana_program::program::invoke_signed(
+    &ix,
+    &ToAccountInfos::to_account_infos(&ctx),
+    ctx.signer_seeds,
+  )
+  .map_err(Into::into)
+}
+
+#[derive(Accounts)]
+pub struct UpdateV1<'info> {
+  /// CHECK: Used in CPI So no Harm
+  pub authority: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub mint: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub metadata: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub payer: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub system_program: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub sysvar_instructions: AccountInfo<'info>,
+}
+
+/// Updates the data of a programmable NFT as its update authority, which the
+/// legacy instructions reject. Moves it to `collection`, unverified, if given,
+/// otherwise the collection and uses are left unchanged.
+pub fn update_v1<'info>(
+  ctx: CpiContext<'_, '_, '_, 'info, UpdateV1<'info>>,
+  data: DataV2,
+  collection: Option<Pubkey>,
+) -> Result<()> {
+  let mut args = UpdateArgs::default_v1();
+  if let UpdateArgs::V1 { data: args_data, collection: args_collection, .. } = &mut args {
+    *args_data = Some(Data {
+      name: data.name,
+      symbol: data.symbol,
+      uri: data.uri,
+      seller_fee_basis_points: data.seller_fee_basis_points,
+      creators: data.creators,
+    });
+    if let Some(key) = collection {
+      *args_collection = CollectionToggle::Set(Collection { verified: false, key });
+    }
+  }
+  let ix = UpdateBuilder::new()
+    .authority(*ctx.accounts.authority.key)
+    .mint(*ctx.accounts.mint.key)
+    .metadata(*ctx.accounts.metadata.key)
+    .payer(*ctx.accounts.payer.key)
+    .system_program(*ctx.accounts.system_program.key)
+    .sysvar_instructions(*ctx.accounts.sysvar_instructions.key)
+    .build(args)
+    .map_err(|_| error!(ErrorCode::InvalidDataProvided))?
+    .instruction();
+  solana_program::program::invoke_signed(
+    &ix,
+    &ToAccountInfos::to_account_infos(&ctx),
+    ctx.signer_seeds,
+  )
+  .map_err(Into::into)
+}
+
+#[derive(Accounts)]
+pub struct SetCollectionSize<'info> {
+  /// CHECK: Used in CPI So no Harm
+  pub metadata: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub update_authority: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub mint: AccountInfo<'info>,
+}
+
+/// Turns an unsized collection into a sized one. Token metadata only allows this once.
+pub fn set_collection_size<'info>(
+  ctx: CpiContext<'_, '_, '_, 'info, SetCollectionSize<'info>>,
+  size: u64,
+) -> Result<()> {
+  let ix = mpl_token_metadata::instruction::set_collection_size(
+    ID,
+    *ctx.accounts.metadata.key,
+    *ctx.accounts.update_authority.key,
+    *ctx.accounts.mint.key,
+    None,
+    size,
+  );
+  solana_program::program::invoke_signed(
+    &ix,
+    &ToAccountInfos::to_account_infos(&ctx),
+    ctx.signer_seeds,
+  )
+  .map_err(Into::into)
+}
+
+#[derive(Accounts)]
+pub struct CreateV1<'info> {
+  /// CHECK: Used in CPI So no Harm
+  pub metadata: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub master_edition: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub mint: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub authority: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub payer: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub update_authority: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub system_program: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub sysvar_instructions: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub spl_token_program: AccountInfo<'info>,
+}
+
+/// Creates the metadata and master edition of an existing, still empty mint.
+pub fn create_v1<'info>(
+  ctx: CpiContext<'_, '_, '_, 'info, CreateV1<'info>>,
+  asset_data: AssetData,
+) -> Result<()> {
+  let ix = CreateBuilder::new()
+    .metadata(*ctx.accounts.metadata.key)
+    .master_edition(*ctx.accounts.master_edition.key)
+    .mint(*ctx.accounts.mint.key)
+    .authority(*ctx.accounts.authority.key)
+    .payer(*ctx.accounts.payer.key)
+    .update_authority(*ctx.accounts.update_authority.key)
+    .system_program(*ctx.accounts.system_program.key)
+    .sysvar_instructions(*ctx.accounts.sysvar_instructions.key)
+    .spl_token_program(*ctx.accounts.spl_token_program.key)
+    .initialize_mint(false)
+    .update_authority_as_signer(true)
+    .build(CreateArgs::V1 {
+      asset_data,
+      decimals: Some(0),
+      print_supply: Some(PrintSupply::Zero),
+    })
+    .map_err(|_| error!(ErrorCode::InvalidDataProvided))?
+    .instruction();
+  solana_program::program::invoke_signed(
+    &ix,
+    &ToAccountInfos::to_account_infos(&ctx),
+    ctx.signer_seeds,
+  )
+  .map_err(Into::into)
+}
+
+#[derive(Accounts)]
+pub struct MintV1<'info> {
+  /// CHECK: Used in CPI So no Harm
+  pub token: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub token_owner: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub metadata: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub master_edition: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub token_record: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub mint: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub authority: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub payer: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub system_program: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub sysvar_instructions: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub spl_token_program: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub spl_ata_program: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub authorization_rules_program: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub authorization_rules: AccountInfo<'info>,
+}
+
+/// Mints the single token of a programmable NFT, which token metadata keeps frozen.
+pub fn mint_v1<'info>(ctx: CpiContext<'_, '_, '_, 'info, MintV1<'info>>) -> Result<()> {
+  let ix = MintBuilder::new()
+    .token(*ctx.accounts.token.key)
+    .token_owner(*ctx.accounts.token_owner.key)
+    .metadata(*ctx.accounts.metadata.key)
+    .master_edition(*ctx.accounts.master_edition.key)
+    .token_record(*ctx.accounts.token_record.key)
+    .mint(*ctx.accounts.mint.key)
+    .authority(*ctx.accounts.authority.key)
+    .payer(*ctx.accounts.payer.key)
+    .system_program(*ctx.accounts.system_program.key)
+    .sysvar_instructions(*ctx.accounts.sysvar_instructions.key)
+    .spl_token_program(*ctx.accounts.spl_token_program.key)
+    .spl_ata_program(*ctx.accounts.spl_ata_program.key)
+    .authorization_rules_program(*ctx.accounts.authorization_rules_program.key)
+    .authorization_rules(*ctx.accounts.authorization_rules.key)
+    .build(MintArgs::V1 {
+      amount: 1,
+      authorization_data: None,
+    })
+    .map_err(|_| error!(ErrorCode::InvalidDataProvided))?
+    .instruction();
+  solana_program::program::invoke_signed(
+    &ix,
+    &ToAccountInfos::to_account_infos(&ctx),
+    ctx.signer_seeds,
+  )
+  .map_err(Into::into)
+}
+
+#[derive(Accounts)]
+pub struct VerifyCollectionV1<'info> {
+  /// CHECK: Used in CPI So no Harm
+  pub authority: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub metadata: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub collection_mint: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub collection_metadata: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub collection_master_edition: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub system_program: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub sysvar_instructions: AccountInfo<'info>,
+}
+
+/// Verifies a programmable NFT in the collection, which the legacy instructions reject.
+pub fn verify_collection_v1<'info>(
+  ctx: CpiContext<'_, '_, '_, 'info, VerifyCollectionV1<'info>>,
+) -> Result<()> {
+  let ix = VerifyBuilder::new()
+    .authority(*ctx.accounts.authority.key)
+    .metadata(*ctx.accounts.metadata.key)
+    .collection_mint(*ctx.accounts.collection_mint.key)
+    .collection_metadata(*ctx.accounts.collection_metadata.key)
+    .collection_master_edition(*ctx.accounts.collection_master_edition.key)
+    .system_program(*ctx.accounts.system_program.key)
+    .sysvar_instructions(*ctx.accounts.sysvar_instructions.key)
+    .build(VerificationArgs::CollectionV1)
+    .map_err(|_| error!(ErrorCode::InvalidDataProvided))?
+    .instruction();
+  solana_program::program::invoke_signed(
+    &ix,
+    &ToAccountInfos::to_account_infos(&ctx),
+    ctx.signer_seeds,
+  )
+  .map_err(Into::into)
+}
+
+#[derive(Accounts)]
+pub struct UnverifyCollectionV1<'info> {
+  /// CHECK: Used in CPI So no Harm
+  pub authority: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub metadata: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub collection_mint: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub collection_metadata: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub system_program: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub sysvar_instructions: AccountInfo<'info>,
+}
+
+/// Unverifies an NFT of any standard from the collection, decrementing its
+/// size if sized.
+pub fn unverify_collection_v1<'info>(
+  ctx: CpiContext<'_, '_, '_, 'info, UnverifyCollectionV1<'info>>,
+) -> Result<()> {
+  let ix = UnverifyBuilder::new()
+    .authority(*ctx.accounts.authority.key)
+    .metadata(*ctx.accounts.metadata.key)
+    .collection_mint(*ctx.accounts.collection_mint.key)
+    .collection_metadata(*ctx.accounts.collection_metadata.key)
+    .system_program(*ctx.accounts.system_program.key)
+    .sysvar_instructions(*ctx.accounts.sysvar_instructions.key)
+    .build(VerificationArgs::CollectionV1)
+    .map_err(|_| error!(ErrorCode::InvalidDataProvided))?
+    .instruction();
+  solana_program::program::invoke_signed(
+    &ix,
+    &ToAccountInfos::to_account_infos(&ctx),
+    ctx.signer_seeds,
+  )
+  .map_err(Into::into)
+}
+
+#[derive(Accounts)]
+pub struct SignMetadata<'info> {
+  /// CHECK: Used in CPI So no Harm
+  pub metadata: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub creator: AccountInfo<'info>,
+}
+
+/// Marks the signing creator as verified on the metadata.
+pub fn sign_metadata<'info>(ctx: CpiContext<'_, '_, '_, 'info, SignMetadata<'info>>) -> Result<()> {
+  let ix = mpl_token_metadata::instruction::sign_metadata(
+    ID,
+    *ctx.accounts.metadata.key,
+    *ctx.accounts.creator.key,
+  );
+  solana_program::program::invoke_signed(
+    &ix,
+    &ToAccountInfos::to_account_infos(&ctx),
+    ctx.signer_seeds,
+  )
+  .map_err(Into::into)
+}
+
+#[derive(Accounts)]
+pub struct VerifyCreatorV1<'info> {
+  /// CHECK: Used in CPI So no Harm
+  pub creator: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub metadata: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub system_program: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub sysvar_instructions: AccountInfo<'info>,
+}
+
+/// `sign_metadata` for programmable NFTs.
+pub fn verify_creator_v1<'info>(
+  ctx: CpiContext<'_, '_, '_, 'info, VerifyCreatorV1<'info>>,
+) -> Result<()> {
+  let ix = VerifyBuilder::new()
+    .authority(*ctx.accounts.creator.key)
+    .metadata(*ctx.accounts.metadata.key)
+    .system_program(*ctx.accounts.system_program.key)
+    .sysvar_instructions(*ctx.accounts.sysvar_instructions.key)
+    .build(VerificationArgs::CreatorV1)
+    .map_err(|_| error!(ErrorCode::InvalidDataProvided))?
+    .instruction();
+  solana_program::program::invoke_signed(
+    &ix,
+    &ToAccountInfos::to_account_infos(&ctx),
+    ctx.signer_seeds,
+  )
+  .map_err(Into::into)
+}
+
+#[derive(Accounts)]
+pub struct BurnV1<'info> {
+  /// CHECK: Used in CPI So no Harm
+  pub authority: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub collection_metadata: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub metadata: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub edition: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub mint: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub token: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub token_record: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub system_program: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub sysvar_instructions: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub spl_token_program: AccountInfo<'info>,
+}
+
+/// Burns a programmable NFT and closes its token, token record, metadata and
+/// edition accounts, sending their rent to the owner.
+pub fn burn_v1<'info>(ctx: CpiContext<'_, '_, '_, 'info, BurnV1<'info>>) -> Result<()> {
+  let ix = BurnBuilder::new()
+    .authority(*ctx.accounts.authority.key)
+    .collection_metadata(*ctx.accounts.collection_metadata.key)
+    .metadata(*ctx.accounts.metadata.key)
+    .edition(*ctx.accounts.edition.key)
+    .mint(*ctx.accounts.mint.key)
+    .token(*ctx.accounts.token.key)
+    .token_record(*ctx.accounts.token_record.key)
+    .system_program(*ctx.accounts.system_program.key)
+    .sysvar_instructions(*ctx.accounts.sysvar_instructions.key)
+    .spl_token_program(*ctx.accounts.spl_token_program.key)
+    .build(BurnArgs::V1 { amount: 1 })
+    .map_err(|_| error!(ErrorCode::InvalidDataProvided))?
+    .instruction();
+  solana_program::program::invoke_signed(
+    &ix,
+    &ToAccountInfos::to_account_infos(&ctx),
+    ctx.signer_seeds,
+  )
+  .map_err(Into::into)
+}
+
+#[derive(Accounts)]
+pub struct CreateOrUpdateRuleSet<'info> {
+  /// CHECK: Used in CPI So no Harm
+  pub payer: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub rule_set_pda: AccountInfo<'info>,
+  /// CHECK: Used in CPI So no Harm
+  pub system_program: AccountInfo<'info>,
+}
+
+/// Writes a MessagePack serialized RuleSet. The payer is also its owner.
+pub fn create_or_update_rule_set<'info>(
+  ctx: CpiContext<'_, '_, '_, 'info, CreateOrUpdateRuleSet<'info>>,
+  serialized_rule_set: Vec<u8>,
+) -> Result<()> {
+  let ix = CreateOrUpdateBuilder::new()
+    .payer(*ctx.accounts.payer.key)
+    .rule_set_pda(*ctx.accounts.rule_set_pda.key)
+    .system_program(*ctx.accounts.system_program.key)
+    .build(CreateOrUpdateArgs::V1 { serialized_rule_set })
+    .map_err(|_| error!(ErrorCode::InvalidDataProvided))?
+    .instruction();
+  solana_program::program::invoke_signed(
+    &ix,
+    &ToAccountInfos::to_account_infos(&ctx),
+    ctx.signer_seeds,
+  )
+  .map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::state::SanctionsProof;
+
+  fn key(byte: u8) -> Pubkey {
+    Pubkey::new_from_array([byte; 32])
+  }
+
+  fn sanctions_leaf(low: Pubkey, high: Pubkey) -> [u8; 32] {
+    SanctionsProof { low, high, proof: vec![] }.leaf()
+  }
+
+  #[test]
+  fn sanctions_proof_rejects_an_internal_node_as_leaf() {
+    let leaves = [
+      sanctions_leaf(key(0x00), key(0x10)),
+      sanctions_leaf(key(0x10), key(0x20)),
+      sanctions_leaf(key(0x20), key(0x30)),
+      sanctions_leaf(key(0x30), key(0xff)),
+    ];
+    let left = hash_sorted_pair(&leaves[0], &leaves[1]);
+    let right = hash_sorted_pair(&leaves[2], &leaves[3]);
+    let root = hash_sorted_pair(&left, &right);
+
+    assert!(verify_merkle_proof(&[leaves[1], right], &root, leaves[0]));
+
+    // the children of `left` read as the neighbours of a leaf covering every
+    // wallet between them
+    let (low, high) = if leaves[0] <= leaves[1] {
+      (leaves[0], leaves[1])
+    } else {
+      (leaves[1], leaves[0])
+    };
+    let forged = SanctionsProof {
+      low: Pubkey::new_from_array(low),
+      high: Pubkey::new_from_array(high),
+      proof: vec![right],
+    };
+    assert!(!verify_merkle_proof(&forged.proof, &root, forged.leaf()));
+  }
+}
@@ -21,6 +21,9 @@ import {
     getCollectionMasterEditionAddress,
 
     getCollectionVaultAccount,
+    findTier,
+    findTierMint,
+    findTierVault,
 } from './utils/utils'
 
 import { ADMIN_WALLET, TOKEN_METADATA_PROGRAM_ID, TOKEN_PROGRAM_ID, USER_WALLET } from "./utils/constants";
@@ -94,4 +97,124 @@ describe("initialize", () => {
             return;
         }
     });
+
+    it("sucessed:update_collection_metadata", async () => {
+
+        const nonTransferableProject = await findNonTransferableProject();
+        const nonTransferableProjectMint = await getCollectionMintAddress();
+        const nonTransferableProjectMetadata = await getCollectionMetadataAddress(nonTransferableProjectMint);
+
+        const uri = "https://dev-api-1.rns.id/api/v2/portal/identity/collection/metadata/"
+        await program.methods
+            .updateCollectionMetadata({
+                name: "Legal DID",
+                symbol: 'LDID',
+                uri: uri,
+                creators: [
+                    { address: nonTransferableProject, share: 0 },
+                    { address: ADMIN_WALLET.publicKey, share: 100 },
+                ],
+                sellerFeeBasisPoints: 500,
+            })
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+                nonTransferableProjectMint: nonTransferableProjectMint,
+                nonTransferableProjectMetadata: nonTransferableProjectMetadata,
+                tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+            })
+            .signers([ADMIN_WALLET])
+            .rpc();
+
+        const context = createUmi().use(defaultPlugins("http://localhost:8899", { commitment: "processed" }));
+        const metadata = await fetchMetadataFromSeeds(context, { mint: nonTransferableProjectMint as any });
+
+        assert(metadata.uri == uri && metadata.sellerFeeBasisPoints == 500, "collection metadata not updated!")
+    });
+
+    it("failed: set_collection_size on a sized collection", async () => {
+
+        const nonTransferableProject = await findNonTransferableProject();
+        const nonTransferableProjectMint = await getCollectionMintAddress();
+        const nonTransferableProjectMetadata = await getCollectionMetadataAddress(nonTransferableProjectMint);
+
+        try {
+            await program.methods
+                .setCollectionSize(new BN(0))
+                .accounts({
+                    authority: ADMIN_WALLET.publicKey,
+                    nonTransferableProject: nonTransferableProject,
+                    nonTransferableProjectMint: nonTransferableProjectMint,
+                    nonTransferableProjectMetadata: nonTransferableProjectMetadata,
+                    tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+                })
+                .signers([ADMIN_WALLET])
+                .rpc();
+            assert(false, "a new collection must already be sized!")
+        } catch ({ error }) {
+            assert(error.errorCode.code == 'CollectionAlreadySized', "CollectionAlreadySized")
+        }
+    });
+
+    it("sucessed:create_tier", async () => {
+
+        const nonTransferableProject = await findNonTransferableProject();
+        const tier = 1;
+        const tierMint = findTierMint(tier);
+
+        await program.methods
+            .createTier(tier, {
+                name: "Legal DID Enhanced",
+                symbol: 'LDID',
+                uri: "https://dev-api-1.rns.id/api/v2/portal/identity/collection/metadata/enhanced/",
+            }, {
+                baseUri: "https://dev-api-1.rns.id/api/v2/portal/identity/nft/enhanced/",
+                mintPrice: new BN(200),
+                maxSupply: new BN(0),
+                isActive: true,
+            })
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+                tierAccount: findTier(tier),
+                tierMint: tierMint,
+                tierVault: findTierVault(tier),
+                tierMetadata: await getCollectionMetadataAddress(tierMint),
+                tierMasterEdition: await getCollectionMasterEditionAddress(tierMint),
+                tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: web3.SystemProgram.programId,
+                rent: SYSVAR_RENT_PUBKEY,
+            })
+            .signers([ADMIN_WALLET])
+            .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 1_000_000 })])
+            .rpc();
+
+        const data = await program.account.tierAccount.fetch(findTier(tier))
+        assert(data.mint.equals(tierMint) && data.mintPrice.toNumber() == 200, "tier not created!")
+    });
+
+    it("sucessed:update_tier", async () => {
+
+        const nonTransferableProject = await findNonTransferableProject();
+        const tier = 1;
+
+        await program.methods
+            .updateTier(tier, {
+                baseUri: "https://dev-api-1.rns.id/api/v2/portal/identity/nft/enhanced/",
+                mintPrice: new BN(300),
+                maxSupply: new BN(10),
+                isActive: false,
+            })
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+                tierAccount: findTier(tier),
+            })
+            .signers([ADMIN_WALLET])
+            .rpc();
+
+        const data = await program.account.tierAccount.fetch(findTier(tier))
+        assert(data.mintPrice.toNumber() == 300 && !data.isActive, "tier not updated!")
+    });
 });
@@ -17,6 +17,8 @@ import {
     getOwnershipAccountBump,
     getOwnershipAccountAddress,
     findNonTransferableProject,
+    findNonTransferableProjectV1,
+    findRuleSet,
     // getCollectionAccount
 } from './utils/utils'
 import { assert } from 'chai';
@@ -24,6 +26,7 @@ import { Keypair, LAMPORTS_PER_SOL, PublicKey } from '@solana/web3.js';
 import {
     ADMIN_WALLET,
     USER_WALLET,
+    AUTH_RULES_PROGRAM_ID,
  } from './utils/constants';
 
 
@@ -69,6 +72,50 @@ describe("config settings", () => {
         assert(mintPrice.toString() == _collection.mintPrice.toString(), 'mintPrice not eq!');
     });
 
+    it("sucessed:schedule_mint_price", async () => {
+
+        const nonTransferableProject = await findNonTransferableProject();
+
+        const pendingPrice = new BN(200);
+        const effectiveAt = new BN(Math.floor(Date.now() / 1000) + 24 * 60 * 60);
+
+        await program.methods
+            .scheduleMintPrice(pendingPrice, effectiveAt)
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+            })
+            .signers([
+                ADMIN_WALLET
+            ])
+            .rpc();
+
+        const _collection = await program.account.projectAccount.fetch(nonTransferableProject)
+        assert(pendingPrice.eq(_collection.pendingMintPrice), 'pendingMintPrice not eq!');
+        assert(effectiveAt.eq(_collection.pendingPriceEffectiveAt), 'pendingPriceEffectiveAt not eq!');
+        assert(_collection.mintPrice.toNumber() == 100, 'the scheduled price must not apply yet!');
+    });
+
+    it("sucessed:set_mint_price clears the scheduled price", async () => {
+
+        const nonTransferableProject = await findNonTransferableProject();
+
+        await program.methods
+            .setMintPrice(new BN(100))
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+            })
+            .signers([
+                ADMIN_WALLET
+            ])
+            .rpc();
+
+        const _collection = await program.account.projectAccount.fetch(nonTransferableProject)
+        assert(_collection.pendingMintPrice.toNumber() == 0, 'pendingMintPrice must be cleared!');
+        assert(_collection.pendingPriceEffectiveAt.toNumber() == 0, 'pendingPriceEffectiveAt must be cleared!');
+    });
+
     it("sucessed:set_fee_recipient!", async () => {
 
         const nonTransferableProject = await findNonTransferableProject();
@@ -116,6 +163,109 @@ describe("config settings", () => {
 
     });
 
+    it("sucessed:set_referral_reward", async () => {
+
+        const nonTransferableProject = await findNonTransferableProject();
+
+        await program.methods
+            .setReferralReward(1_000)
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+            })
+            .signers([
+                ADMIN_WALLET
+            ])
+            .rpc();
+
+        const data = await program.account.projectAccount.fetch(nonTransferableProject)
+
+        assert(data.referralRewardBps == 1_000, "referral reward setting failed!")
+    });
+
+    it("sucessed:set_max_wallets_per_rns_id", async () => {
+
+        const nonTransferableProject = await findNonTransferableProject();
+
+        await program.methods
+            .setMaxWalletsPerRnsId(new BN(3))
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+            })
+            .signers([
+                ADMIN_WALLET
+            ])
+            .rpc();
+
+        const data = await program.account.projectAccount.fetch(nonTransferableProject)
+
+        assert(data.maxWalletsPerRnsId.toNumber() == 3, "max wallets setting failed!")
+    });
+
+    it("sucessed:set_uri_template", async () => {
+
+        const nonTransferableProject = await findNonTransferableProject();
+
+        const setUriTemplate = (template: string) => program.methods
+            .setUriTemplate(template)
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+            })
+            .signers([
+                ADMIN_WALLET
+            ])
+            .rpc();
+
+        await setUriTemplate('https://did.rns.id/render/{mint}?status={status}');
+        const data = await program.account.projectAccount.fetch(nonTransferableProject)
+        assert(data.uriTemplate == 'https://did.rns.id/render/{mint}?status={status}', "uri template setting failed!")
+
+        // later tests expect the base_uri scheme
+        await setUriTemplate('');
+    });
+
+    it("sucessed:set_name_template", async () => {
+
+        const nonTransferableProject = await findNonTransferableProject();
+
+        await program.methods
+            .setNameTemplate('{name} #{index}')
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+            })
+            .signers([
+                ADMIN_WALLET
+            ])
+            .rpc();
+
+        const data = await program.account.projectAccount.fetch(nonTransferableProject)
+        assert(data.nameTemplate == '{name} #{index}', "name template setting failed!")
+    });
+
+    it("failed: set_name_template too long", async () => {
+
+        const nonTransferableProject = await findNonTransferableProject();
+
+        try {
+            await program.methods
+                .setNameTemplate('{name} '.repeat(10))
+                .accounts({
+                    authority: ADMIN_WALLET.publicKey,
+                    nonTransferableProject: nonTransferableProject,
+                })
+                .signers([
+                    ADMIN_WALLET
+                ])
+                .rpc();
+            assert(false, "the template must fit the project account!")
+        } catch ({ error }) {
+            assert(error.errorCode.code == "NameTemplateTooLong", "wrong error!")
+        }
+    });
+
     it("sucessed:set_is_blocked_address", async () => {
 
         const nonTransferableProject = await findNonTransferableProject();
@@ -141,6 +291,183 @@ describe("config settings", () => {
 
     });
 
+    it("sucessed:set_jurisdiction_denied", async () => {
+
+        const nonTransferableProject = await findNonTransferableProject();
+
+        await program.methods
+            .setJurisdictionDenied([...Buffer.from('KP')], true)
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+            })
+            .signers([
+                ADMIN_WALLET
+            ])
+            .rpc();
+
+        const data = await program.account.projectAccount.fetch(nonTransferableProject)
+        assert(data.deniedJurisdictions.some(code => Buffer.from(code).toString() == 'KP'), "jurisdiction not denied!")
+    });
+
+    it("sucessed:lift a jurisdiction denial", async () => {
+
+        const nonTransferableProject = await findNonTransferableProject();
+
+        // an authorization needs an attested jurisdiction while any code is denied
+        await program.methods
+            .setJurisdictionDenied([...Buffer.from('KP')], false)
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+            })
+            .signers([
+                ADMIN_WALLET
+            ])
+            .rpc();
+
+        const data = await program.account.projectAccount.fetch(nonTransferableProject)
+        assert(data.deniedJurisdictions.length == 0, "jurisdiction still denied!")
+    });
+
+    it("failed: set_jurisdiction_denied with a malformed code", async () => {
+
+        const nonTransferableProject = await findNonTransferableProject();
+
+        try {
+            await program.methods
+                .setJurisdictionDenied([...Buffer.from('k1')], true)
+                .accounts({
+                    authority: ADMIN_WALLET.publicKey,
+                    nonTransferableProject: nonTransferableProject,
+                })
+                .signers([
+                    ADMIN_WALLET
+                ])
+                .rpc();
+            assert(false, "codes must be two uppercase letters!")
+        } catch ({ error }) {
+            assert(error.errorCode.code == "InvalidJurisdiction", "wrong error!")
+        }
+    });
+
+    it("sucessed:set_sanctions_root by the compliance authority", async () => {
+
+        const nonTransferableProject = await findNonTransferableProject();
+        const compliance = Keypair.generate();
+        const sanctionsRoot = [...Buffer.alloc(32, 7)];
+
+        await program.methods
+            .setComplianceAuthority(compliance.publicKey)
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+            })
+            .signers([
+                ADMIN_WALLET
+            ])
+            .rpc();
+
+        await program.methods
+            .setSanctionsRoot(sanctionsRoot, new BN(1))
+            .accounts({
+                authority: compliance.publicKey,
+                nonTransferableProject: nonTransferableProject,
+            })
+            .signers([
+                compliance
+            ])
+            .rpc();
+
+        let data = await program.account.projectAccount.fetch(nonTransferableProject)
+        assert(Buffer.from(data.sanctionsRoot).equals(Buffer.from(sanctionsRoot)), "sanctions root not stored!")
+
+        // clear the list again so the following tests are not screened
+        await program.methods
+            .setSanctionsRoot([...Buffer.alloc(32)], new BN(0))
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+            })
+            .signers([
+                ADMIN_WALLET
+            ])
+            .rpc();
+
+        data = await program.account.projectAccount.fetch(nonTransferableProject)
+        assert(data.sanctionsRoot.every(byte => byte == 0), "sanctions root not cleared!")
+    });
+
+    it("failed: set_sanctions_root by an unknown signer", async () => {
+
+        const nonTransferableProject = await findNonTransferableProject();
+
+        try {
+            await program.methods
+                .setSanctionsRoot([...Buffer.alloc(32, 1)], new BN(1))
+                .accounts({
+                    authority: USER_WALLET.publicKey,
+                    nonTransferableProject: nonTransferableProject,
+                })
+                .signers([
+                    USER_WALLET
+                ])
+                .rpc();
+            assert(false, "only the authority or compliance authority may set the root!")
+        } catch ({ error }) {
+            assert(error.errorCode.code == "InvalidAuthority", "wrong error!")
+        }
+    });
+
+    it("sucessed:set_allowlist_root", async () => {
+
+        const nonTransferableProject = await findNonTransferableProject();
+        const allowlistRoot = [...Buffer.alloc(32, 9)];
+
+        await program.methods
+            .setAllowlistRoot(allowlistRoot, new BN(1))
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+            })
+            .signers([
+                ADMIN_WALLET
+            ])
+            .rpc();
+
+        const data = await program.account.projectAccount.fetch(nonTransferableProject)
+        assert(Buffer.from(data.allowlistRoot).equals(Buffer.from(allowlistRoot)), "allowlist root not stored!")
+        assert(!data.allowlistMode, "setting the root must not enable allowlist mode!")
+    });
+
+    it("sucessed:set_next_token_index past indexes minted before the counter", async () => {
+
+        const nonTransferableProject = await findNonTransferableProject();
+        const setNextTokenIndex = (index: BN) => program.methods
+            .setNextTokenIndex(index)
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+            })
+            .signers([
+                ADMIN_WALLET
+            ])
+            .rpc();
+
+        // "0" to "4" stand for indexes chosen by callers before the counter existed
+        await setNextTokenIndex(new BN(5));
+
+        const data = await program.account.projectAccount.fetch(nonTransferableProject)
+        assert(data.nextTokenIndex.toNumber() == 5, "next token index not set!")
+
+        try {
+            await setNextTokenIndex(new BN(4));
+            assert(false, "the counter must not move back onto minted indexes!")
+        } catch ({ error }) {
+            assert(error.errorCode.code == "InvalidTokenIndex", "wrong error!")
+        }
+    });
+
     it("sucessed:set_is_blocked_rns_id", async () => {
         const nonTransferableProject = await findNonTransferableProject();
         const rns_id = "3"
@@ -164,6 +491,120 @@ describe("config settings", () => {
 
     });
 
+    it("failed: migrate_account on an up-to-date project", async () => {
+        const nonTransferableProject = await findNonTransferableProject();
+
+        const data = await program.account.projectAccount.fetch(nonTransferableProject)
+        assert(data.version == 1, "new accounts must carry the current version!")
+
+        try {
+            await program.methods
+                .migrateAccount({ project: {} }, web3.PublicKey.default)
+                .accounts({
+                    authority: ADMIN_WALLET.publicKey,
+                    nonTransferableProject: nonTransferableProject,
+                    account: nonTransferableProject,
+                    rentReceiver: ADMIN_WALLET.publicKey,
+                    nonTransferableWalletIndex: null,
+                    systemProgram: web3.SystemProgram.programId,
+                })
+                .signers([
+                    ADMIN_WALLET
+                ])
+                .rpc();
+            assert(false, "a current account must not be migrated again!")
+        } catch ({ error }) {
+            assert(error.errorCode.code == 'AlreadyMigrated', "AlreadyMigrated")
+        }
+    });
+
+    it("failed: import_v1_project without a v1 project", async () => {
+        const nonTransferableProject = await findNonTransferableProject();
+
+        try {
+            await program.methods
+                .importV1Project()
+                .accounts({
+                    authority: ADMIN_WALLET.publicKey,
+                    nonTransferableProject: nonTransferableProject,
+                    v1Project: findNonTransferableProjectV1(),
+                })
+                .signers([
+                    ADMIN_WALLET
+                ])
+                .rpc();
+            assert(false, "there is no v1 project to import on a fresh chain!")
+        } catch ({ error }) {
+            assert(error != undefined, "import must fail")
+        }
+    });
+
+    it("sucessed:create_rule_set", async () => {
+
+        const nonTransferableProject = await findNonTransferableProject();
+        const ruleSet = findRuleSet(nonTransferableProject);
+
+        await program.methods
+            .createRuleSet()
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+                ruleSet: ruleSet,
+                authorizationRulesProgram: AUTH_RULES_PROGRAM_ID,
+                systemProgram: web3.SystemProgram.programId,
+            })
+            .signers([ADMIN_WALLET])
+            .rpc();
+
+        const data = await program.account.projectAccount.fetch(nonTransferableProject)
+        assert(data.ruleSet.equals(ruleSet), "rule set not recorded!")
+        // the other tests issue legacy DIDs
+        assert(!data.programmable, "creating the rule set must not switch the issuance mode!")
+
+        const info = await provider.connection.getAccountInfo(ruleSet)
+        assert(info != null && info.owner.equals(AUTH_RULES_PROGRAM_ID), "rule set not created!")
+    });
+
+    it("sucessed:set_creators", async () => {
+
+        const nonTransferableProject = await findNonTransferableProject();
+        const issuer = Keypair.generate().publicKey;
+
+        await program.methods
+            .setCreators([
+                { address: ADMIN_WALLET.publicKey, share: 50 },
+                { address: issuer, share: 50 },
+            ])
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+            })
+            .signers([ADMIN_WALLET])
+            .rpc();
+
+        const data = await program.account.projectAccount.fetch(nonTransferableProject)
+        assert(data.creators.length == 2 && data.creators[1].address.equals(issuer), "creators not recorded!")
+    });
+
+    it("failed: set_creators not sharing 100%", async () => {
+
+        const nonTransferableProject = await findNonTransferableProject();
+
+        try {
+            await program.methods
+                .setCreators([{ address: ADMIN_WALLET.publicKey, share: 90 }])
+                .accounts({
+                    authority: ADMIN_WALLET.publicKey,
+                    nonTransferableProject: nonTransferableProject,
+                })
+                .signers([ADMIN_WALLET])
+                .rpc();
+            assert(false, "shares must total 100!")
+        } catch ({ error }) {
+            assert(error.errorCode.code == "InvalidCreators", "wrong error!")
+        }
+    });
+
  
 
 });
@@ -19,11 +19,17 @@ import {
     getOwnershipAccountBump,
     getOwnershipAccountAddress,
     findNonTransferableProject,
+    findTier,
     // getCollectionAccount
 
     findNonTransferableUserStatus,
+    findSponsorReceipt,
+    findAllowlistEntry,
+    rnsIdCommitment,
+    commitmentMessage,
+    authorizeMintArgs,
 } from './utils/utils'
-import { LAMPORTS_PER_SOL, PublicKey, SYSVAR_RENT_PUBKEY } from '@solana/web3.js';
+import { Ed25519Program, Keypair, LAMPORTS_PER_SOL, PublicKey, SYSVAR_INSTRUCTIONS_PUBKEY, SYSVAR_RENT_PUBKEY } from '@solana/web3.js';
 import {
     rnsId,
     ADMIN_WALLET,
@@ -54,8 +60,15 @@ describe("authorize_mint", () => {
         accounts = {
             authority: USER_WALLET.publicKey,
             nonTransferableProject: nonTransferableProject,
+            tier: null,
             nonTransferableUserStatus: nonTransferableUserStatus,
-            feeRecipient: ADMIN_WALLET.publicKey,
+            sponsorReceipt: null,
+            campaign: null,
+            voucherRedemption: null,
+            allowlistEntry: null,
+            referrerNftStatus: null,
+            referralStats: null,
+            instructions: null,
             systemProgram: web3.SystemProgram.programId,
             rent: SYSVAR_RENT_PUBKEY,
         };
@@ -76,7 +89,7 @@ describe("authorize_mint", () => {
     it("successed: authorize_mint", async () => {
 
         await program.methods
-            .authorizeMint(rnsId, USER_WALLET.publicKey)
+            .authorizeMint(rnsId, USER_WALLET.publicKey, authorizeMintArgs())
             .accounts(accounts)
             .signers([
                 USER_WALLET
@@ -96,7 +109,7 @@ describe("authorize_mint", () => {
 
         try {
             await program.methods
-                .authorizeMint(rnsId, USER_WALLET.publicKey)
+                .authorizeMint(rnsId, USER_WALLET.publicKey, authorizeMintArgs())
                 .accounts(accounts)
                 .signers([
                     USER_WALLET
@@ -110,4 +123,357 @@ describe("authorize_mint", () => {
         }
     })
 
+    it("failed: mint price exceeds max_price", async () => {
+
+        const slippageRnsId = 'slippage-' + Date.now().toString();
+
+        try {
+            await program.methods
+                .authorizeMint(slippageRnsId, USER_WALLET.publicKey, authorizeMintArgs({ maxPrice: new BN(0) }))
+                .accounts({
+                    ...accounts,
+                    nonTransferableUserStatus: findNonTransferableUserStatus(slippageRnsId, USER_WALLET.publicKey),
+                })
+                .signers([
+                    USER_WALLET
+                ])
+                .rpc();
+            assert(false, "max_price must be enforced!")
+        } catch ({ error }) {
+            assert(error.errorCode.code == 'PriceExceedsMax', "PriceExceedsMax")
+        }
+    })
+
+    it("failed: rns_id too long", async () => {
+
+        const longRnsId = 'x'.repeat(65);
+
+        try {
+            await program.methods
+                .authorizeMint(longRnsId, USER_WALLET.publicKey, authorizeMintArgs())
+                .accounts({
+                    ...accounts,
+                    nonTransferableUserStatus: findNonTransferableUserStatus(longRnsId, USER_WALLET.publicKey),
+                })
+                .signers([
+                    USER_WALLET
+                ])
+                .rpc();
+            assert(false, "rns_id length must be checked!")
+        } catch ({ error }) {
+            assert(error.errorCode.code == 'RnsIdTooLong', "RnsIdTooLong")
+        }
+    })
+
+    it("failed: authorize_mint for a blocked rns_id", async () => {
+
+        const blockedRnsId = 'blocked-' + Date.now().toString();
+
+        await program.methods
+            .setIsBlockedRnsId(blockedRnsId, true)
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+            })
+            .signers([
+                ADMIN_WALLET
+            ])
+            .rpc();
+
+        try {
+            await program.methods
+                .authorizeMint(blockedRnsId, USER_WALLET.publicKey, authorizeMintArgs())
+                .accounts({
+                    ...accounts,
+                    nonTransferableUserStatus: findNonTransferableUserStatus(blockedRnsId, USER_WALLET.publicKey),
+                })
+                .signers([
+                    USER_WALLET
+                ])
+                .rpc();
+            assert(false, "blocked rns_ids can't be authorized!")
+        } catch ({ error }) {
+            assert(error.errorCode.code == 'LdidBlacklisted', "LdidBlacklisted")
+        }
+    })
+
+    const setJurisdictionDenied = (isDenied: boolean) => program.methods
+        .setJurisdictionDenied([...Buffer.from('KP')], isDenied)
+        .accounts({
+            authority: ADMIN_WALLET.publicKey,
+            nonTransferableProject: nonTransferableProject,
+        })
+        .signers([
+            ADMIN_WALLET
+        ])
+        .rpc();
+
+    it("failed: authorize_mint in a denied jurisdiction", async () => {
+
+        const deniedRnsId = 'denied-' + Date.now().toString();
+        await setJurisdictionDenied(true);
+
+        try {
+            await program.methods
+                .authorizeMint(deniedRnsId, USER_WALLET.publicKey, authorizeMintArgs({ jurisdiction: [...Buffer.from('KP')] }))
+                .accounts({
+                    ...accounts,
+                    nonTransferableUserStatus: findNonTransferableUserStatus(deniedRnsId, USER_WALLET.publicKey),
+                })
+                .signers([
+                    USER_WALLET
+                ])
+                .rpc();
+            assert(false, "KP is denied by set_jurisdiction_denied!")
+        } catch ({ error }) {
+            assert(error.errorCode.code == 'JurisdictionDenied', "JurisdictionDenied")
+        }
+    })
+
+    it("failed: authorize_mint without a jurisdiction while some are denied", async () => {
+
+        const deniedRnsId = 'undeclared-' + Date.now().toString();
+
+        try {
+            await program.methods
+                .authorizeMint(deniedRnsId, USER_WALLET.publicKey, authorizeMintArgs())
+                .accounts({
+                    ...accounts,
+                    nonTransferableUserStatus: findNonTransferableUserStatus(deniedRnsId, USER_WALLET.publicKey),
+                })
+                .signers([
+                    USER_WALLET
+                ])
+                .rpc();
+            assert(false, "a jurisdiction must be attested while KP is denied!")
+        } catch ({ error }) {
+            assert(error.errorCode.code == 'JurisdictionRequired', "JurisdictionRequired")
+        } finally {
+            await setJurisdictionDenied(false);
+        }
+    })
+
+    it("failed: authorize_mint for an inactive tier", async () => {
+
+        const tierRnsId = 'tier-' + Date.now().toString();
+
+        try {
+            await program.methods
+                .authorizeMint(tierRnsId, USER_WALLET.publicKey, authorizeMintArgs())
+                .accounts({
+                    ...accounts,
+                    tier: findTier(1),
+                    nonTransferableUserStatus: findNonTransferableUserStatus(tierRnsId, USER_WALLET.publicKey),
+                })
+                .signers([
+                    USER_WALLET
+                ])
+                .rpc();
+            assert(false, "tier 1 is deactivated by update_tier!")
+        } catch ({ error }) {
+            assert(error.errorCode.code == 'TierInactive', "TierInactive")
+        }
+    })
+
+    it("successed: sponsored authorize_mint for another wallet", async () => {
+
+        const beneficiary = Keypair.generate().publicKey;
+        const sponsoredRnsId = 'sponsored-' + Date.now().toString();
+        const userStatus = findNonTransferableUserStatus(sponsoredRnsId, beneficiary);
+        const sponsorReceipt = findSponsorReceipt(userStatus);
+
+        await program.methods
+            .authorizeMint(sponsoredRnsId, beneficiary, authorizeMintArgs())
+            .accounts({
+                ...accounts,
+                nonTransferableUserStatus: userStatus,
+                sponsorReceipt: sponsorReceipt,
+            })
+            .signers([
+                USER_WALLET
+            ])
+            .rpc();
+
+        const data = await program.account.userStatusAccount.fetch(userStatus)
+        assert(data.authority.equals(beneficiary), "status must belong to the beneficiary!")
+
+        const receipt = await program.account.sponsorReceiptAccount.fetch(sponsorReceipt)
+        assert(receipt.sponsor.equals(USER_WALLET.publicKey) && receipt.beneficiary.equals(beneficiary), "sponsor receipt mismatch!")
+    })
+
+    it("successed: cancel_authorization with refund, then authorize again", async () => {
+
+        const cancelledRnsId = 'cancelled-' + Date.now().toString();
+        const userStatus = findNonTransferableUserStatus(cancelledRnsId, USER_WALLET.publicKey);
+
+        const authorize = () => program.methods
+            .authorizeMint(cancelledRnsId, USER_WALLET.publicKey, authorizeMintArgs())
+            .accounts({ ...accounts, nonTransferableUserStatus: userStatus })
+            .signers([USER_WALLET])
+            .rpc();
+
+        await authorize();
+
+        const escrowed = await provider.connection.getBalance(userStatus);
+
+        // the fee is escrowed on the user status, the user is refunded without the treasury
+        await program.methods
+            .cancelAuthorization(cancelledRnsId, USER_WALLET.publicKey)
+            .accounts({
+                authority: USER_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+                nonTransferableUserStatus: userStatus,
+                payer: USER_WALLET.publicKey,
+            })
+            .signers([USER_WALLET])
+            .rpc();
+
+        const refunded = await provider.connection.getBalance(userStatus);
+        assert(refunded < escrowed, "escrowed fee not refunded!")
+
+        const cancelled = await program.account.userStatusAccount.fetch(userStatus)
+        assert(!cancelled.isAuthorized && cancelled.paidAmount.toNumber() == 0, "authorization not cancelled!")
+
+        await authorize();
+
+        const data = await program.account.userStatusAccount.fetch(userStatus)
+        assert(data.isAuthorized, "cancelled authorization must be renewable!")
+    })
+
+    it("successed: privacy mode only accepts commitments", async () => {
+
+        const setPrivacyMode = (enabled: boolean) => program.methods
+            .setPrivacyMode(enabled)
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+            })
+            .signers([ADMIN_WALLET])
+            .rpc();
+
+        await program.methods
+            .setCommitmentAttestor(ADMIN_WALLET.publicKey)
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+            })
+            .signers([ADMIN_WALLET])
+            .rpc();
+
+        const plainRnsId = 'private-' + Date.now().toString();
+        const commitment = rnsIdCommitment(plainRnsId, Keypair.generate().publicKey.toBase58());
+        const commitmentAccounts = {
+            ...accounts,
+            nonTransferableUserStatus: findNonTransferableUserStatus(commitment, USER_WALLET.publicKey),
+            instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        };
+
+        await setPrivacyMode(true);
+        try {
+            try {
+                await program.methods
+                    .authorizeMint(plainRnsId, USER_WALLET.publicKey, authorizeMintArgs())
+                    .accounts({
+                        ...accounts,
+                        nonTransferableUserStatus: findNonTransferableUserStatus(plainRnsId, USER_WALLET.publicKey),
+                    })
+                    .signers([USER_WALLET])
+                    .rpc();
+                assert(false, "plaintext rns_id must be rejected!")
+            } catch ({ error }) {
+                assert(error.errorCode.code == 'RnsIdNotCommitted', "RnsIdNotCommitted")
+            }
+
+            try {
+                await program.methods
+                    .authorizeMint(commitment, USER_WALLET.publicKey, authorizeMintArgs())
+                    .accounts(commitmentAccounts)
+                    .signers([USER_WALLET])
+                    .rpc();
+                assert(false, "a commitment with a caller-chosen salt must be rejected!")
+            } catch ({ error }) {
+                assert(error.errorCode.code == 'InvalidDataProvided', "InvalidDataProvided")
+            }
+
+            await program.methods
+                .authorizeMint(commitment, USER_WALLET.publicKey, authorizeMintArgs())
+                .accounts(commitmentAccounts)
+                .preInstructions([
+                    Ed25519Program.createInstructionWithPrivateKey({
+                        privateKey: ADMIN_WALLET.secretKey,
+                        message: commitmentMessage(USER_WALLET.publicKey, commitment),
+                    }),
+                ])
+                .signers([USER_WALLET])
+                .rpc();
+        } finally {
+            await setPrivacyMode(false);
+        }
+
+        const data = await program.account.userStatusAccount.fetch(findNonTransferableUserStatus(commitment, USER_WALLET.publicKey))
+        assert(data.isAuthorized, "committed rns_id must be authorized!")
+    });
+
+    it("successed: allowlist mode only authorizes allowlisted wallets", async () => {
+
+        const setAllowlistMode = (enabled: boolean) => program.methods
+            .setAllowlistMode(enabled)
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+            })
+            .signers([ADMIN_WALLET])
+            .rpc();
+
+        const subject = [...USER_WALLET.publicKey.toBytes()];
+        const allowlistEntry = findAllowlistEntry(USER_WALLET.publicKey.toBuffer());
+        const entryAccounts = {
+            authority: ADMIN_WALLET.publicKey,
+            nonTransferableProject: nonTransferableProject,
+            allowlistEntry: allowlistEntry,
+        };
+        const authorize = (allowlistRnsId: string, entry: PublicKey | null) => program.methods
+            .authorizeMint(allowlistRnsId, USER_WALLET.publicKey, authorizeMintArgs())
+            .accounts({
+                ...accounts,
+                nonTransferableUserStatus: findNonTransferableUserStatus(allowlistRnsId, USER_WALLET.publicKey),
+                allowlistEntry: entry,
+            })
+            .signers([USER_WALLET])
+            .rpc();
+
+        const deniedRnsId = 'allowlist-denied-' + Date.now().toString();
+        const allowedRnsId = 'allowlist-allowed-' + Date.now().toString();
+
+        await setAllowlistMode(true);
+        try {
+            try {
+                await authorize(deniedRnsId, null);
+                assert(false, "wallets without an entry must be rejected!")
+            } catch ({ error }) {
+                assert(error.errorCode.code == 'NotAllowlisted', "NotAllowlisted")
+            }
+
+            await program.methods
+                .addAllowlistEntry(subject)
+                .accounts({ ...entryAccounts, systemProgram: web3.SystemProgram.programId })
+                .signers([ADMIN_WALLET])
+                .rpc();
+
+            await authorize(allowedRnsId, allowlistEntry);
+
+            await program.methods
+                .removeAllowlistEntry(subject)
+                .accounts(entryAccounts)
+                .signers([ADMIN_WALLET])
+                .rpc();
+        } finally {
+            await setAllowlistMode(false);
+        }
+
+        const data = await program.account.userStatusAccount.fetch(findNonTransferableUserStatus(allowedRnsId, USER_WALLET.publicKey))
+        assert(data.isAuthorized, "allowlisted wallet must be authorized!")
+        assert(await provider.connection.getAccountInfo(allowlistEntry) == null, "allowlist entry not closed!")
+    });
+
 });
@@ -9,7 +9,9 @@ import {
     AnchorProvider,
     BN,
 } from '@project-serum/anchor'
-import { Metadata, Edition } from '@metaplex-foundation/mpl-token-metadata';
+import { Metadata, Edition, fetchMetadataFromSeeds } from '@metaplex-foundation/mpl-token-metadata';
+import { createUmi } from '@metaplex-foundation/umi';
+import { defaultPlugins } from '@metaplex-foundation/umi-bundle-defaults';
 
 import {
 
@@ -28,13 +30,17 @@ import {
 
     getUserAssociatedTokenAccount,
     getNonTransferableNftMintAddress,
+    findWalletIndex,
+    findNftAttributes,
     getCollectionMintBump,
     getTokenAccountBalance,
     findNonTransferableUserStatus,
     findNonTransferableNftStatus,
     findNonTransferableRnsIdtatus,
     getAccountNFTs,
-    getTokenAccountDetails
+    getTokenAccountDetails,
+    getNextTokenIndex,
+    authorizeMintArgs
 } from './utils/utils'
 
 
@@ -42,7 +48,6 @@ import {
     ADMIN_WALLET, TOKEN_METADATA_PROGRAM_ID, TOKEN_PROGRAM_ID,
     USER_WALLET,
     rnsId,
-    tokenIndex,
     merkleRoot
 } from "./utils/constants";
 import { assert } from 'chai';
@@ -67,11 +72,13 @@ describe("airdrop", () => {
     let userAssociatedTokenAccount;
     let nonTransferableNftStatus;
     let nonTransferableRnsIdStatus;
+    let tokenIndex;
 
     mint_to_pubkey = USER_WALLET.publicKey;
 
     before(async () => {
         nonTransferableProject = await findNonTransferableProject();
+        tokenIndex = await getNextTokenIndex(program, nonTransferableProject);
         nonTransferableProjectMint = await getCollectionMintAddress();
         nonTransferableProjectMetadata = await getCollectionMetadataAddress(nonTransferableProjectMint);
         nonTransferableProjectMasterEdition = await getCollectionMasterEditionAddress(nonTransferableProjectMint);
@@ -107,6 +114,12 @@ describe("airdrop", () => {
             nonTransferableUserStatus: nonTransferableUserStatus,
             nonTransferableNftStatus: nonTransferableNftStatus,
             nonTransferableRnsIdStatus: nonTransferableRnsIdStatus,
+            nonTransferableWalletIndex: findWalletIndex(mint_to_pubkey),
+            nonTransferableNftAttributes: null,
+
+            // verify pays the escrowed fee out to the fee recipient
+            feeRecipient: ADMIN_WALLET.publicKey,
+            referralStats: null,
 
             nonTransferableNftMint: nonTransferableNftMint,
             nonTransferableNftMetadata: nonTransferableNftMetadata,
@@ -117,6 +130,15 @@ describe("airdrop", () => {
             nonTransferableProjectMetadata: nonTransferableProjectMetadata,
             nonTransferableProjectMasterEdition: nonTransferableProjectMasterEdition,
 
+            // only programmable DIDs need these
+            nonTransferableNftTokenRecord: null,
+            sysvarInstructions: null,
+            authorizationRulesProgram: null,
+            authorizationRules: null,
+
+            // issued in the project collection
+            tier: null,
+
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
             tokenProgram: TOKEN_PROGRAM_ID,
@@ -133,15 +155,19 @@ describe("airdrop", () => {
             mint_to_pubkey,
             merkleRoot,
             tokenIndex,
+            null,
             {
                 accounts
             })
 
+        // issued without an attested jurisdiction, the operator supplies it
         await program.methods.airdrop(
             rnsId,
             mint_to_pubkey,
             merkleRoot,
-            tokenIndex
+            tokenIndex,
+            [...Buffer.from('SG')],
+            null
         )
             .accounts(accounts)
             .preInstructions([set_compute_unit_limit_ix])
@@ -162,21 +188,57 @@ describe("airdrop", () => {
         const data = await program.account.nftStatusAccount.fetch(nonTransferableNftStatus)
         assert(rnsId == data.rnsId, 'rnsId')
         assert(mint_to_pubkey.toBase58() == data.authority.toBase58(), 'authority')
-        assert(merkleRoot.toString() == data.merkleRoot.toString(), 'merkleRoot')
+        assert(merkleRoot == Buffer.from(data.merkleRoot).toString('hex'), 'merkleRoot')
         assert(nonTransferableNftMint.toBase58() == data.mint.toBase58(), 'mint')
+        assert(Buffer.from(data.jurisdiction).toString() == 'SG', 'jurisdiction')
 
-        const { isAuthorized, isMinted } = await program.account.userStatusAccount.fetch(nonTransferableUserStatus)
+        const { isAuthorized, isMinted, paidAmount, referralReward } = await program.account.userStatusAccount.fetch(nonTransferableUserStatus)
         assert(isMinted, "did 's is_minted must be true!")
+        assert(paidAmount.toNumber() == 0 && referralReward.toNumber() == 0, "verify must pay the escrowed fee out!")
+
+        assert(data.tokenIndex.toString() == tokenIndex, 'tokenIndex')
+        assert(await getNextTokenIndex(program, nonTransferableProject) == (Number(tokenIndex) + 1).toString(), 'token counter must advance')
+
+        const walletIndex = await program.account.walletIndexAccount.fetch(findWalletIndex(mint_to_pubkey))
+        assert(walletIndex.entries.some(e => e.mint.equals(nonTransferableNftMint) && e.status.active), 'wallet index must list the DID')
 
 
     });
 
 
+    it("failed: authorize_mint again after mint", async () => {
+
+        try {
+            await program.methods
+                .authorizeMint(rnsId, mint_to_pubkey, authorizeMintArgs())
+                .accounts({
+                    authority: USER_WALLET.publicKey,
+                    nonTransferableProject: nonTransferableProject,
+                    tier: null,
+                    nonTransferableUserStatus: nonTransferableUserStatus,
+                    sponsorReceipt: null,
+                    campaign: null,
+                    voucherRedemption: null,
+                    allowlistEntry: null,
+                    referrerNftStatus: null,
+                    referralStats: null,
+                    instructions: null,
+                    systemProgram: web3.SystemProgram.programId,
+                    rent: SYSVAR_RENT_PUBKEY,
+                })
+                .signers([USER_WALLET])
+                .rpc();
+            assert(false, "a minted DID can't be authorized again!")
+        } catch ({ error }) {
+            assert(error.errorCode.code == 'LDIDHasAuthorized', "LDIDHasAuthorized")
+        }
+    });
+
     it("sucessed:set_merkle_root", async () => {
         const nonTransferableProject = await findNonTransferableProject();
 
         const data_before = await program.account.nftStatusAccount.fetch(nonTransferableNftStatus)
-        assert(data_before.rnsId == rnsId && data_before.merkleRoot == merkleRoot, "set_merkle_root setting failed!")
+        assert(data_before.rnsId == rnsId && Buffer.from(data_before.merkleRoot).toString('hex') == merkleRoot, "set_merkle_root setting failed!")
 
         const merkleRoot_2 = '2d852b3c21e923484a93d3a980a45b7571e89552d58875d40dd17c73216a49d8';
         await program.methods
@@ -193,8 +255,65 @@ describe("airdrop", () => {
             .rpc();
 
         const data = await program.account.nftStatusAccount.fetch(nonTransferableNftStatus)
-        assert(data.rnsId == rnsId && data.merkleRoot == merkleRoot_2, "set_merkle_root setting failed!")
+        assert(data.rnsId == rnsId && Buffer.from(data.merkleRoot).toString('hex') == merkleRoot_2, "set_merkle_root setting failed!")
+
+    });
+
+    it("sucessed:set_nft_attributes", async () => {
+        const nonTransferableProject = await findNonTransferableProject();
+        const nonTransferableNftAttributes = findNftAttributes(nonTransferableNftMint);
+
+        await program.methods
+            .setNftAttributes({
+                kycLevel: 2,
+                issuer: ADMIN_WALLET.publicKey,
+                expiresAt: new BN(0),
+                jurisdiction: [...Buffer.from('SG')],
+            })
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+                nonTransferableNftMint: nonTransferableNftMint,
+                nonTransferableNftStatus: nonTransferableNftStatus,
+                nonTransferableNftAttributes: nonTransferableNftAttributes,
+                systemProgram: web3.SystemProgram.programId,
+            })
+            .signers([
+                ADMIN_WALLET
+            ])
+            .rpc();
+
+        const data = await program.account.nftAttributesAccount.fetch(nonTransferableNftAttributes)
+        assert(data.kycLevel == 2 && Buffer.from(data.jurisdiction).toString() == 'SG' && data.issuedAt.toNumber() > 0 && data.status.active, "set_nft_attributes failed!")
+    });
+
+    it("sucessed:refresh_metadata", async () => {
+        const nonTransferableProject = await findNonTransferableProject();
+
+        await program.methods
+            .refreshMetadata()
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+                tier: null,
+                sysvarInstructions: null,
+                tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+                systemProgram: web3.SystemProgram.programId,
+            })
+            .remainingAccounts([
+                { pubkey: nonTransferableNftStatus, isWritable: false, isSigner: false },
+                { pubkey: nonTransferableNftMetadata, isWritable: true, isSigner: false },
+                { pubkey: nonTransferableNftMint, isWritable: false, isSigner: false },
+            ])
+            .signers([
+                ADMIN_WALLET
+            ])
+            .rpc();
 
+        const context = createUmi().use(defaultPlugins("http://localhost:8899", { commitment: "processed" }));
+        const metadata = await fetchMetadataFromSeeds(context, { mint: nonTransferableNftMint as any });
+        const project = await program.account.projectAccount.fetch(nonTransferableProject)
+        assert(metadata.uri == project.baseUri + rnsId + '.json', "metadata uri must follow base_uri!")
     });
 
     it('minted number should be eq 1', async () => {
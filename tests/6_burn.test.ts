@@ -33,7 +33,9 @@ import {
   findNonTransferableUserStatus,
   findFreezeAuthority,
   findNonTransferableNftStatus,
-  findNonTransferableRnsIdtatus
+  findNonTransferableRnsIdtatus,
+  findWalletIndex,
+  getNextTokenIndex
 
 } from './utils/utils'
 
@@ -43,8 +45,7 @@ import {
      USER_WALLET,
      TOKEN_METADATA_PROGRAM_ID,
      TOKEN_PROGRAM_ID,
-     rnsId,
-     tokenIndex } from "./utils/constants";
+     rnsId } from "./utils/constants";
 import { MintLayout, createInitializeMintInstruction } from '@solana/spl-token';
 import { assert } from 'chai';
 import { PublicKey } from '@solana/web3.js';
@@ -68,6 +69,8 @@ describe("burn", () => {
     const collectionMintAddress = await getCollectionMintAddress();
     const collectionMetadataAddress = await getCollectionMetadataAddress(collectionMintAddress);
 
+    // the DID minted by the airdrop test holds the last allocated index
+    const tokenIndex = (Number(await getNextTokenIndex(program, collectionAddress)) - 1).toString();
     const nonTransferableNftMint = await getNonTransferableNftMintAddress(rnsId, tokenIndex);
     const userTokenAccount = await getUserAssociatedTokenAccount(userPubkey, nonTransferableNftMint)
 
@@ -91,10 +94,16 @@ describe("burn", () => {
         nonTransferableNftMint: nonTransferableNftMint,
         nonTransferableNftMetadata: nonTransferableNftMetadata,
         nonTransferableNftMasterEdition: nonTransferableNftMasterEdition,
+        nonTransferableNftTokenRecord: null,
+        sysvarInstructions: null,
 
         nonTransferableUserStatus: nonTransferableUserStatus,
         nonTransferableNftStatus: nonTransferableNftStatus,
         nonTransferableRnsIdStatus: nonTransferableRnsIdStatus,
+        nonTransferableWalletIndex: findWalletIndex(userPubkey),
+        nonTransferableNftAttributes: null,
+        nftRentPayer: ADMIN_WALLET.publicKey,
+        userStatusRentPayer: userPubkey,
 
         nonTransferableProject: collectionAddress,
         nonTransferableProjectMint: collectionMintAddress,
@@ -108,14 +117,21 @@ describe("burn", () => {
       .signers([USER_WALLET])
       .rpc();
 
-    const details_after = await getTokenAccountDetails(userTokenAccount)
+    // the token, metadata and edition accounts are closed by the burn
+    assert(await provider.connection.getAccountInfo(userTokenAccount) == null, 'token account must be closed');
+    assert(await provider.connection.getAccountInfo(nonTransferableNftMetadata) == null, 'metadata must be closed');
+    assert(await provider.connection.getAccountInfo(nonTransferableNftMasterEdition) == null, 'edition must be closed');
 
-    assert(details_after.amount == BigInt(0), '==');
+    const nftStatus = await program.account.nftStatusAccount.fetch(nonTransferableNftStatus)
+    assert(nftStatus.isBurned && nftStatus.rnsId == rnsId, "burned status must be kept as history!")
+
+    const walletIndex = await program.account.walletIndexAccount.fetch(findWalletIndex(userPubkey))
+    assert(walletIndex.entries.some(e => e.mint.equals(nonTransferableNftMint) && e.status.burned), "wallet index must mark the DID as burned!")
 
     after(async () => {
 
-        const {isAuthorized, isMinted} = await program.account.userStatusAccount.fetch(nonTransferableUserStatus)
-        assert(!isMinted, "did 's is_minted must be false!")
+        const userStatus = await program.account.userStatusAccount.fetchNullable(nonTransferableUserStatus)
+        assert(userStatus == null, "user status must be closed!")
 
     })
   });
@@ -0,0 +1,130 @@
+import { RnsdidCore } from '../target/types/rnsdid_core'
+
+import {
+    Program,
+    web3,
+    workspace,
+    setProvider,
+    AnchorProvider,
+    BN,
+} from '@project-serum/anchor'
+import {
+    findNonTransferableProject,
+    findNonTransferableUserStatus,
+    findCampaign,
+    findVoucherRedemption,
+    authorizeMintArgs,
+} from './utils/utils'
+import { Keypair, LAMPORTS_PER_SOL, SYSVAR_RENT_PUBKEY } from '@solana/web3.js';
+import { ADMIN_WALLET } from './utils/constants';
+import { assert } from 'chai';
+const crypto = require('crypto');
+
+
+describe("discount campaign", () => {
+
+    const provider = AnchorProvider.env();
+    setProvider(provider);
+
+    const program = workspace.RnsdidCore as Program<RnsdidCore>;
+
+    const campaignId = new BN(Date.now());
+    const wallet = Keypair.generate();
+    const campaignRnsId = crypto.randomUUID();
+
+    let nonTransferableProject;
+    let campaign;
+
+    before(async () => {
+        nonTransferableProject = await findNonTransferableProject();
+        campaign = findCampaign(campaignId);
+
+        const airdropSignature = await provider.connection.requestAirdrop(wallet.publicKey, LAMPORTS_PER_SOL);
+        await provider.connection.confirmTransaction(airdropSignature);
+    })
+
+    it("successed: create_campaign", async () => {
+        // a single-leaf allowlist: the root is the leaf itself, hashed under the 0x00 leaf prefix
+        const merkleRoot = crypto.createHash('sha256').update(Buffer.from([0])).update(wallet.publicKey.toBuffer()).digest();
+
+        await program.methods
+            .createCampaign(campaignId, {
+                kind: { merkle: {} },
+                issuer: web3.PublicKey.default,
+                merkleRoot: [...merkleRoot],
+                discountKind: { percentage: {} },
+                discountValue: new BN(10_000),
+                maxUses: new BN(1),
+                isActive: true,
+            })
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+                sponsorReceipt: null,
+                campaign: campaign,
+                systemProgram: web3.SystemProgram.programId,
+            })
+            .signers([ADMIN_WALLET])
+            .rpc();
+
+        const data = await program.account.campaignAccount.fetch(campaign)
+        assert(data.maxUses.toNumber() == 1 && data.used.toNumber() == 0, "campaign setting failed!")
+    });
+
+    it("successed: authorize_mint for free with a voucher", async () => {
+        await program.methods
+            .authorizeMint(campaignRnsId, wallet.publicKey, authorizeMintArgs({ voucher: { campaignId, proof: [] } }))
+            .accounts({
+                authority: wallet.publicKey,
+                nonTransferableProject: nonTransferableProject,
+                tier: null,
+                nonTransferableUserStatus: findNonTransferableUserStatus(campaignRnsId, wallet.publicKey),
+                sponsorReceipt: null,
+                campaign: campaign,
+                voucherRedemption: findVoucherRedemption(campaignId, wallet.publicKey),
+                allowlistEntry: null,
+                referrerNftStatus: null,
+                referralStats: null,
+                instructions: null,
+                systemProgram: web3.SystemProgram.programId,
+                rent: SYSVAR_RENT_PUBKEY,
+            })
+            .signers([wallet])
+            .rpc();
+
+        const status = await program.account.userStatusAccount.fetch(findNonTransferableUserStatus(campaignRnsId, wallet.publicKey))
+        assert(status.paidAmount.toNumber() == 0, "a 100% voucher must not charge a fee!")
+
+        const data = await program.account.campaignAccount.fetch(campaign)
+        assert(data.used.toNumber() == 1, "campaign usage not tracked!")
+    });
+
+    it("failed: campaign exhausted", async () => {
+        const otherRnsId = crypto.randomUUID();
+
+        try {
+            await program.methods
+                .authorizeMint(otherRnsId, wallet.publicKey, authorizeMintArgs({ voucher: { campaignId, proof: [] } }))
+                .accounts({
+                    authority: wallet.publicKey,
+                    nonTransferableProject: nonTransferableProject,
+                    tier: null,
+                    nonTransferableUserStatus: findNonTransferableUserStatus(otherRnsId, wallet.publicKey),
+                        sponsorReceipt: null,
+                    campaign: campaign,
+                    voucherRedemption: findVoucherRedemption(campaignId, wallet.publicKey),
+                    allowlistEntry: null,
+                    referrerNftStatus: null,
+                    referralStats: null,
+                    instructions: null,
+                    systemProgram: web3.SystemProgram.programId,
+                    rent: SYSVAR_RENT_PUBKEY,
+                })
+                .signers([wallet])
+                .rpc();
+            assert(false, "voucher redeemed twice!")
+        } catch ({ error }) {
+            assert(error != undefined, "redemption must fail")
+        }
+    });
+});
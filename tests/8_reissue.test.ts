@@ -0,0 +1,199 @@
+import { RnsdidCore } from '../target/types/rnsdid_core'
+
+import { ASSOCIATED_TOKEN_PROGRAM_ID } from '@solana/spl-token'
+import {
+    Program,
+    web3,
+    workspace,
+    setProvider,
+    AnchorProvider,
+} from '@project-serum/anchor'
+
+import {
+    findNonTransferableProject,
+    getCollectionMetadataAddress,
+    getCollectionMintAddress,
+    getCollectionMasterEditionAddress,
+    getUserAssociatedTokenAccount,
+    getNonTransferableNftMintAddress,
+    findNonTransferableUserStatus,
+    findNonTransferableNftStatus,
+    findNonTransferableRnsIdtatus,
+    findWalletIndex,
+    getNextTokenIndex,
+    authorizeMintArgs
+} from './utils/utils'
+
+import {
+    ADMIN_WALLET, TOKEN_METADATA_PROGRAM_ID, TOKEN_PROGRAM_ID,
+    USER_WALLET,
+    rnsId,
+    merkleRoot
+} from "./utils/constants";
+import { assert } from 'chai';
+import { ComputeBudgetProgram, SYSVAR_INSTRUCTIONS_PUBKEY } from '@solana/web3.js';
+import { fetchMetadataFromSeeds } from '@metaplex-foundation/mpl-token-metadata';
+import { createUmi } from '@metaplex-foundation/umi';
+import { defaultPlugins } from '@metaplex-foundation/umi-bundle-defaults';
+const { SYSVAR_RENT_PUBKEY } = web3
+
+describe("reissue", () => {
+
+    const provider = AnchorProvider.env();
+    setProvider(provider)
+    const program = workspace.RnsdidCore as Program<RnsdidCore>;
+
+    it("successed: reissue a burned DID", async () => {
+
+        const wallet = USER_WALLET.publicKey;
+        const nonTransferableProject = await findNonTransferableProject();
+        const nonTransferableProjectMint = await getCollectionMintAddress();
+
+        // the DID burned by the burn test holds the last allocated index
+        const tokenIndex = await getNextTokenIndex(program, nonTransferableProject);
+        const previousNftMint = getNonTransferableNftMintAddress(rnsId, (Number(tokenIndex) - 1).toString());
+        const previousNftStatus = await findNonTransferableNftStatus(previousNftMint);
+
+        const nonTransferableNftMint = getNonTransferableNftMintAddress(rnsId, tokenIndex);
+        const nonTransferableNftStatus = await findNonTransferableNftStatus(nonTransferableNftMint);
+
+        const accounts = {
+            authority: ADMIN_WALLET.publicKey,
+
+            userAccount: wallet,
+            userTokenAccount: await getUserAssociatedTokenAccount(wallet, nonTransferableNftMint),
+            nonTransferableUserStatus: findNonTransferableUserStatus(rnsId, wallet),
+            nonTransferableNftStatus: nonTransferableNftStatus,
+            nonTransferableRnsIdStatus: await findNonTransferableRnsIdtatus(rnsId),
+            nonTransferableWalletIndex: findWalletIndex(wallet),
+            nonTransferableNftAttributes: null,
+
+            // verify pays the escrowed fee out to the fee recipient
+            feeRecipient: ADMIN_WALLET.publicKey,
+            referralStats: null,
+
+            nonTransferableNftMint: nonTransferableNftMint,
+            nonTransferableNftMetadata: await getCollectionMetadataAddress(nonTransferableNftMint),
+            nonTransferableNftMasterEdition: await getCollectionMasterEditionAddress(nonTransferableNftMint),
+
+            nonTransferableProject: nonTransferableProject,
+            nonTransferableProjectMint: nonTransferableProjectMint,
+            nonTransferableProjectMetadata: await getCollectionMetadataAddress(nonTransferableProjectMint),
+            nonTransferableProjectMasterEdition: await getCollectionMasterEditionAddress(nonTransferableProjectMint),
+
+            // only programmable DIDs need these
+            nonTransferableNftTokenRecord: null,
+            sysvarInstructions: null,
+            authorizationRulesProgram: null,
+            authorizationRules: null,
+
+            // issued in the project collection
+            tier: null,
+
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: web3.SystemProgram.programId,
+            rent: SYSVAR_RENT_PUBKEY,
+        };
+
+        const verify_ix = await program.methods
+            .verify(rnsId, wallet, merkleRoot, tokenIndex, null)
+            .accounts(accounts)
+            .instruction();
+
+        const reissue_ix = await program.methods
+            .reissue(rnsId, wallet)
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+                previousNftStatus: previousNftStatus,
+                nonTransferableNftMint: nonTransferableNftMint,
+                nonTransferableNftStatus: nonTransferableNftStatus,
+            })
+            .instruction();
+
+        await program.methods
+            .airdrop(rnsId, wallet, merkleRoot, tokenIndex, null, null)
+            .accounts(accounts)
+            .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 1_000_000 })])
+            .postInstructions([verify_ix, reissue_ix])
+            .signers([ADMIN_WALLET])
+            .rpc();
+
+        const previous = await program.account.nftStatusAccount.fetch(previousNftStatus)
+        const current = await program.account.nftStatusAccount.fetch(nonTransferableNftStatus)
+
+        assert(previous.reissuedTo.equals(nonTransferableNftMint), 'reissuedTo')
+        assert(current.previousMint.equals(previousNftMint), 'previousMint')
+    });
+
+    it("successed: authorize_mint again after revoke pays the full price", async () => {
+
+        const wallet = USER_WALLET.publicKey;
+        const nonTransferableProject = await findNonTransferableProject();
+        const tokenIndex = (Number(await getNextTokenIndex(program, nonTransferableProject)) - 1).toString();
+        const nonTransferableNftMint = getNonTransferableNftMintAddress(rnsId, tokenIndex);
+        const nonTransferableUserStatus = findNonTransferableUserStatus(rnsId, wallet);
+        const nonTransferableProjectMint = await getCollectionMintAddress();
+
+        const context = createUmi().use(defaultPlugins("http://localhost:8899", { commitment: "processed" }));
+        const collectionSize = async () => {
+            const metadata = await fetchMetadataFromSeeds(context, { mint: nonTransferableProjectMint as any });
+            return (metadata.collectionDetails as any).value.size;
+        };
+        const sizeBefore = await collectionSize();
+
+        await program.methods
+            .revoke(rnsId, wallet)
+            .accounts({
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+                tier: null,
+                nonTransferableProjectMint: nonTransferableProjectMint,
+                nonTransferableProjectMetadata: await getCollectionMetadataAddress(nonTransferableProjectMint),
+                nonTransferableNftMint: nonTransferableNftMint,
+                nonTransferableNftMetadata: await getCollectionMetadataAddress(nonTransferableNftMint),
+                nonTransferableNftStatus: await findNonTransferableNftStatus(nonTransferableNftMint),
+                nonTransferableUserStatus: nonTransferableUserStatus,
+                nonTransferableRnsIdStatus: await findNonTransferableRnsIdtatus(rnsId),
+                nonTransferableWalletIndex: findWalletIndex(wallet),
+                nonTransferableNftAttributes: null,
+                sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+                tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+                systemProgram: web3.SystemProgram.programId,
+            })
+            .signers([ADMIN_WALLET])
+            .rpc();
+
+        assert(await collectionSize() == sizeBefore - BigInt(1), "revoke must leave the sized collection!")
+
+        const revoked = await program.account.userStatusAccount.fetch(nonTransferableUserStatus)
+        assert(revoked.paidAmount.toNumber() == 0 && revoked.referralReward.toNumber() == 0, "revoke must not leave a credit!")
+
+        await program.methods
+            .authorizeMint(rnsId, wallet, authorizeMintArgs())
+            .accounts({
+                authority: USER_WALLET.publicKey,
+                nonTransferableProject: nonTransferableProject,
+                tier: null,
+                nonTransferableUserStatus: nonTransferableUserStatus,
+                sponsorReceipt: null,
+                campaign: null,
+                voucherRedemption: null,
+                allowlistEntry: null,
+                referrerNftStatus: null,
+                referralStats: null,
+                instructions: null,
+                systemProgram: web3.SystemProgram.programId,
+                rent: SYSVAR_RENT_PUBKEY,
+            })
+            .signers([USER_WALLET])
+            .rpc();
+
+        const project = await program.account.projectAccount.fetch(nonTransferableProject)
+        const data = await program.account.userStatusAccount.fetch(nonTransferableUserStatus)
+        assert(data.isAuthorized, "revoked DID must be authorizable again!")
+        assert(data.paidAmount.eq(project.mintPrice), "re-authorization after revoke must pay the full price!")
+    });
+});
@@ -11,6 +11,10 @@ export const TOKEN_METADATA_PROGRAM_ID = new PublicKey(
   'metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s',
 )
 
+export const AUTH_RULES_PROGRAM_ID = new PublicKey(
+  'auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg',
+)
+
 export const TOKEN_PROGRAM_ID = new PublicKey(
   'TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA',
 )
@@ -42,5 +46,4 @@ setProvider(AnchorProvider.env())
 const soulboundProgram = workspace.RnsdidCore as Program<RnsdidCore>
 export const RNSDID_PROGRAM_ID = soulboundProgram.programId
 export const rnsId = 'f1235f17-f746-405e-b5f8-c91d70b72875'
-export const tokenIndex = Date.now().toString()
 export const merkleRoot = '2d852b3c21e923484a93d3a980a45b7571e89552d58875d40dd17c73216a49d7';
@@ -1,9 +1,10 @@
-import { web3, workspace, Program, AnchorProvider, setProvider, getProvider } from '@project-serum/anchor'
+import { web3, workspace, Program, AnchorProvider, setProvider, getProvider, BN } from '@project-serum/anchor'
 const crypto = require('crypto');
 import {
   SPL_ASSOCIATED_TOKEN_ACCOUNT_PROGRAM_ID,
   TOKEN_METADATA_PROGRAM_ID,
   RNSDID_PROGRAM_ID,
+  AUTH_RULES_PROGRAM_ID,
 } from './constants'
 import { AccountLayout, TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, getAccount, MintLayout } from '@solana/spl-token'
 
@@ -170,6 +171,11 @@ export const findNonTransferableProject = () => {
   return (web3.PublicKey.findProgramAddressSync(seeds, RNSDID_PROGRAM_ID))[0]
 }
 
+export const findNonTransferableProjectV1 = () => {
+  const seeds = [Buffer.from("nt-proj")];
+  return (web3.PublicKey.findProgramAddressSync(seeds, RNSDID_PROGRAM_ID))[0]
+}
+
 
 export const getCollectionMintAddress = async () => {
   const seeds = [Buffer.from("nt-project-mint")];
@@ -251,6 +257,105 @@ export const findNonTransferableUserStatus = (rns_id: string, wallet: PublicKey)
   )[0]
 }
 
+export const findCampaign = (campaignId: BN) => {
+  const seeds = [
+    Buffer.from("nt-campaign"),
+    campaignId.toArrayLike(Buffer, 'le', 8),
+  ];
+  return web3.PublicKey.findProgramAddressSync(seeds, RNSDID_PROGRAM_ID)[0];
+};
+
+export const findVoucherRedemption = (campaignId: BN, wallet: PublicKey) => {
+  const seeds = [
+    Buffer.from("nt-voucher-redemption"),
+    campaignId.toArrayLike(Buffer, 'le', 8),
+    wallet.toBuffer(),
+  ];
+  return web3.PublicKey.findProgramAddressSync(seeds, RNSDID_PROGRAM_ID)[0];
+};
+
+export const findSponsorReceipt = (userStatus: PublicKey) => {
+  const seeds = [
+    Buffer.from("nt-sponsor-receipt"),
+    userStatus.toBuffer(),
+  ];
+  return web3.PublicKey.findProgramAddressSync(seeds, RNSDID_PROGRAM_ID)[0];
+};
+
+export const findWalletIndex = (wallet: PublicKey) => {
+  const seeds = [
+    Buffer.from("nt-wallet-index"),
+    wallet.toBuffer(),
+  ];
+  return web3.PublicKey.findProgramAddressSync(seeds, RNSDID_PROGRAM_ID)[0];
+};
+
+export const findNftAttributes = (mint: PublicKey) => {
+  const seeds = [
+    Buffer.from("nt-nft-attributes"),
+    mint.toBuffer(),
+  ];
+  return web3.PublicKey.findProgramAddressSync(seeds, RNSDID_PROGRAM_ID)[0];
+};
+
+export const findTier = (tier: number) => {
+  const seeds = [Buffer.from("nt-tier"), Buffer.from([tier])];
+  return web3.PublicKey.findProgramAddressSync(seeds, RNSDID_PROGRAM_ID)[0];
+};
+
+export const findTierMint = (tier: number) => {
+  const seeds = [Buffer.from("nt-tier-mint"), Buffer.from([tier])];
+  return web3.PublicKey.findProgramAddressSync(seeds, RNSDID_PROGRAM_ID)[0];
+};
+
+export const findTierVault = (tier: number) => {
+  const seeds = [Buffer.from("nt-tier-mint-vault"), Buffer.from([tier])];
+  return web3.PublicKey.findProgramAddressSync(seeds, RNSDID_PROGRAM_ID)[0];
+};
+
+/* `subject` is the wallet key or the sha256 of the rns_id */
+export const findAllowlistEntry = (subject: Buffer) => {
+  const seeds = [Buffer.from("nt-allowlist"), subject];
+  return web3.PublicKey.findProgramAddressSync(seeds, RNSDID_PROGRAM_ID)[0];
+};
+
+/* The token-auth-rules RuleSet owned by the project */
+export const findRuleSet = (project: PublicKey) => {
+  const seeds = [
+    Buffer.from("rule_set"),
+    project.toBuffer(),
+    Buffer.from("rns-did-non-transferable"),
+  ];
+  return web3.PublicKey.findProgramAddressSync(seeds, AUTH_RULES_PROGRAM_ID)[0];
+};
+
+/* Stands in for the rns_id when the project runs in privacy mode */
+export const rnsIdCommitment = (rns_id: string, salt: string) => {
+  return crypto.createHash('sha256').update(salt + rns_id).digest('hex');
+};
+
+/* Message the commitment attestor signs for `wallet` */
+export const commitmentMessage = (wallet: PublicKey, commitment: string) => {
+  return Buffer.concat([wallet.toBuffer(), crypto.createHash('sha256').update(commitment).digest()]);
+};
+
+/* AuthorizeMintArgs with every optional input unset, overridden by `args` */
+export const authorizeMintArgs = (args = {}) => ({
+  voucher: null,
+  referrer: null,
+  maxPrice: null,
+  jurisdiction: null,
+  sanctionsProof: null,
+  allowlistProof: null,
+  ...args,
+});
+
+/* Token indexes are allocated on-chain by the project counter */
+export const getNextTokenIndex = async (program, project: PublicKey): Promise<string> => {
+  const data = await program.account.projectAccount.fetch(project);
+  return data.nextTokenIndex.toString();
+};
+
 export const getNonTransferableNftMintAddress = (rns_id: string, index: String) => {
   const seeds = [
     Buffer.from("nt-nft-mint"),